@@ -28,9 +28,7 @@ fn bench14x14(c: &mut Criterion) {
     let cols = 14;
 
     c.bench_function("grid 14x14", |b| {
-        b.iter(|| {
-            generate_grid(black_box(rows), black_box(cols), black_box(&WORDS));
-        })
+        b.iter(|| generate_grid(black_box(rows), black_box(cols), black_box(&WORDS)))
     });
 }
 
@@ -39,9 +37,7 @@ fn bench15x15(c: &mut Criterion) {
     let cols = 15;
 
     c.bench_function("grid 15x15", |b| {
-        b.iter(|| {
-            generate_grid(black_box(rows), black_box(cols), black_box(&WORDS));
-        })
+        b.iter(|| generate_grid(black_box(rows), black_box(cols), black_box(&WORDS)))
     });
 }
 
@@ -50,11 +46,42 @@ fn bench16x16(c: &mut Criterion) {
     let cols = 16;
 
     c.bench_function("grid 16x16", |b| {
-        b.iter(|| {
-            generate_grid(black_box(rows), black_box(cols), black_box(&WORDS));
-        })
+        b.iter(|| generate_grid(black_box(rows), black_box(cols), black_box(&WORDS)))
     });
 }
 
-criterion_group!(benches, bench14x14, bench15x15, bench16x16);
+fn bench_solvers(c: &mut Criterion) {
+    let grid = generate_grid_from_seed(15, 15, &WORDS, 0).unwrap().grid;
+
+    c.bench_function("solve naive", |b| {
+        b.iter(|| solve_grid_naive(black_box(&grid), black_box(&WORDS)))
+    });
+
+    c.bench_function("solve hash first letter", |b| {
+        b.iter(|| solve_grid_hash_first_letter(black_box(&grid), black_box(&WORDS)))
+    });
+
+    c.bench_function("solve reverse", |b| {
+        b.iter(|| solve_grid_reverse_words(black_box(&grid), black_box(&WORDS)))
+    });
+
+    c.bench_function("solve reverse hash first letter", |b| {
+        b.iter(|| solve_grid_reverse_hash_first_letter(black_box(&grid), black_box(&WORDS)))
+    });
+
+    c.bench_function("solve reverse hash first two letters", |b| {
+        b.iter(|| solve_grid_reverse_hash_first_two_letters(black_box(&grid), black_box(&WORDS)))
+    });
+
+    c.bench_function("solve trie", |b| {
+        b.iter(|| solve_grid_trie(black_box(&grid), black_box(&WORDS)))
+    });
+
+    let index = WordIndex::new(&WORDS).unwrap();
+    c.bench_function("solve trie with reused index", |b| {
+        b.iter(|| index.solve(black_box(&grid)))
+    });
+}
+
+criterion_group!(benches, bench14x14, bench15x15, bench16x16, bench_solvers);
 criterion_main!(benches);
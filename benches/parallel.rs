@@ -0,0 +1,43 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use wordsearch::*;
+
+const WORDS: [&str; 19] = [
+    "anxious",
+    "blossom",
+    "border",
+    "coordinated",
+    "follow",
+    "guess",
+    "hope",
+    "impede",
+    "initiate",
+    "instrument",
+    "mind",
+    "nose",
+    "plausible",
+    "prescribe",
+    "produce",
+    "recite",
+    "robin",
+    "stress",
+    "vivacious",
+];
+
+fn bench300x300(c: &mut Criterion) {
+    let grid = generate_grid_from_seed(300, 300, &WORDS, 0).unwrap().grid;
+
+    // The other strategies walk each ray to the edge of the grid, which takes
+    // seconds per solve at this size.
+    for strategy in &[SolverStrategy::Trie, SolverStrategy::AllOccurrences] {
+        c.bench_function(&format!("solve 300x300 {} sequential", strategy), |b| {
+            b.iter(|| solve(black_box(&grid), black_box(&WORDS), *strategy))
+        });
+
+        c.bench_function(&format!("solve 300x300 {} parallel", strategy), |b| {
+            b.iter(|| solve_parallel(black_box(&grid), black_box(&WORDS), *strategy))
+        });
+    }
+}
+
+criterion_group!(benches, bench300x300);
+criterion_main!(benches);
@@ -0,0 +1,242 @@
+use crate::Cell;
+use std::error;
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WordsearchError {
+    /// No words were provided.
+    EmptyWordList,
+    /// The word cannot fit in the grid in any of the allowed directions.
+    WordTooLong {
+        word: String,
+        max: usize,
+    },
+    /// The word is shorter than the minimum length supported.
+    WordTooShort {
+        word: String,
+        min: usize,
+    },
+    /// The word contains a character that cannot be placed in a grid.
+    InvalidCharacter {
+        word: String,
+        character: char,
+    },
+    /// No directions were allowed for placing words.
+    NoDirections,
+    /// The word appears within another word (or its reverse), so it cannot occur only once.
+    WordWithinWord {
+        word: String,
+        containing: String,
+    },
+    /// Every word fits on its own, but there is no arrangement that fits them all.
+    Unplaceable,
+    /// A grid row has a different number of columns to the first row.
+    RaggedGrid {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The text being parsed as a grid contains no rows.
+    EmptyGrid,
+    /// A line of the text being parsed as a grid has a different number of letters to the first.
+    RaggedLine {
+        line: usize,
+        column: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The word location extends beyond the edge of the grid.
+    LocationOutOfBounds {
+        word: String,
+    },
+    /// The mask for shaping the grid has no open cells.
+    EmptyMask,
+    /// The mask for shaping the grid is a different size to the grid.
+    MaskSizeMismatch {
+        rows: usize,
+        cols: usize,
+        mask_rows: usize,
+        mask_cols: usize,
+    },
+    /// The alphabet for filling the grid has no letters.
+    EmptyAlphabet,
+    /// The alphabet for filling the grid contains a character that cannot be placed in a grid.
+    InvalidFillCharacter(char),
+    /// The puzzle was saved in a newer format than this version of the crate can read.
+    UnsupportedPuzzleVersion(u32),
+    /// A line of the text being parsed as a puzzle is not valid.
+    InvalidPuzzleLine {
+        line: usize,
+        message: String,
+    },
+    /// The text being parsed as a puzzle has no section with this name.
+    MissingPuzzleSection(String),
+    /// The answer key has a location the solver does not find the word at.
+    IncorrectAnswer {
+        word: String,
+        start_cell: Cell,
+        end_cell: Cell,
+    },
+    /// The solver finds the word at a location that is not in the answer key.
+    MissingAnswer {
+        word: String,
+        start_cell: Cell,
+        end_cell: Cell,
+    },
+    /// The text being parsed as a direction does not name one.
+    UnknownDirection(String),
+    /// The text being parsed as a difficulty does not name one.
+    UnknownDifficulty(String),
+    /// The text being parsed as a solver strategy does not name one.
+    UnknownSolverStrategy(String),
+    /// The text being parsed as a language does not name one with letter frequencies.
+    UnknownLanguage(String),
+    /// The text being parsed as a shape is not a built-in shape or a letter from A to Z.
+    UnknownShape(String),
+    /// The text being parsed as a topology does not name one.
+    UnknownTopology(String),
+    /// The template for generating the grid is not the same size as the grid.
+    TemplateSizeMismatch {
+        rows: usize,
+        cols: usize,
+        template_rows: usize,
+        template_cols: usize,
+    },
+    /// A pinned word needs a different letter in a cell than the template, the
+    /// mask or another pinned word put there.
+    PinConflict {
+        word: String,
+        cell: Cell,
+        letter: char,
+        existing: char,
+    },
+    /// The text being parsed as a word location is not valid.
+    InvalidWordLocation {
+        location: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for WordsearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WordsearchError::EmptyWordList => write!(f, "no words provided"),
+            WordsearchError::WordTooLong { word, max } => write!(
+                f,
+                "word '{}' is too long to fit in the grid (maximum length {})",
+                word, max
+            ),
+            WordsearchError::WordTooShort { word, min } => {
+                write!(f, "word '{}' is too short (minimum length {})", word, min)
+            }
+            WordsearchError::InvalidCharacter { word, character } => write!(
+                f,
+                "word '{}' contains invalid character '{}'",
+                word, character
+            ),
+            WordsearchError::WordWithinWord { word, containing } => write!(
+                f,
+                "word '{}' appears within word '{}' so cannot occur only once",
+                word, containing
+            ),
+            WordsearchError::NoDirections => write!(f, "no directions allowed"),
+            WordsearchError::Unplaceable => write!(f, "unable to fit all words in the grid"),
+            WordsearchError::RaggedGrid {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "grid row {} has {} columns but expected {}",
+                row, found, expected
+            ),
+            WordsearchError::EmptyGrid => write!(f, "grid has no rows"),
+            WordsearchError::RaggedLine {
+                line,
+                column,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}, column {}: row has {} letters but expected {}",
+                line, column, found, expected
+            ),
+            WordsearchError::LocationOutOfBounds { word } => {
+                write!(f, "location of word '{}' is outside the grid", word)
+            }
+            WordsearchError::EmptyMask => write!(f, "mask has no open cells"),
+            WordsearchError::MaskSizeMismatch {
+                rows,
+                cols,
+                mask_rows,
+                mask_cols,
+            } => write!(
+                f,
+                "mask is {}x{} but the grid is {}x{}",
+                mask_rows, mask_cols, rows, cols
+            ),
+            WordsearchError::EmptyAlphabet => write!(f, "no letters to fill the grid with"),
+            WordsearchError::InvalidFillCharacter(c) => {
+                write!(f, "cannot fill the grid with invalid character '{}'", c)
+            }
+            WordsearchError::UnsupportedPuzzleVersion(v) => {
+                write!(f, "unsupported puzzle format version {}", v)
+            }
+            WordsearchError::InvalidPuzzleLine { line, message } => {
+                write!(f, "line {}: {}", line, message)
+            }
+            WordsearchError::MissingPuzzleSection(s) => write!(f, "puzzle has no [{}] section", s),
+            WordsearchError::IncorrectAnswer {
+                word,
+                start_cell,
+                end_cell,
+            } => write!(
+                f,
+                "answer key has '{}' from {} to {} but it is not there",
+                word, start_cell, end_cell
+            ),
+            WordsearchError::MissingAnswer {
+                word,
+                start_cell,
+                end_cell,
+            } => write!(
+                f,
+                "answer key is missing '{}' from {} to {}",
+                word, start_cell, end_cell
+            ),
+            WordsearchError::UnknownDirection(s) => write!(f, "unknown direction: {}", s),
+            WordsearchError::UnknownDifficulty(s) => write!(f, "unknown difficulty: {}", s),
+            WordsearchError::UnknownSolverStrategy(s) => {
+                write!(f, "unknown solver strategy: {}", s)
+            }
+            WordsearchError::UnknownLanguage(s) => write!(f, "unknown language: {}", s),
+            WordsearchError::UnknownShape(s) => write!(f, "unknown shape: {}", s),
+            WordsearchError::UnknownTopology(s) => write!(f, "unknown topology: {}", s),
+            WordsearchError::TemplateSizeMismatch {
+                rows,
+                cols,
+                template_rows,
+                template_cols,
+            } => write!(
+                f,
+                "template is {}x{} but the grid is {}x{}",
+                template_rows, template_cols, rows, cols
+            ),
+            WordsearchError::PinConflict {
+                word,
+                cell,
+                letter,
+                existing,
+            } => write!(
+                f,
+                "pinned word '{}' needs '{}' at {} but '{}' is already there",
+                word, letter, cell, existing
+            ),
+            WordsearchError::InvalidWordLocation { location, message } => {
+                write!(f, "invalid word location '{}': {}", location, message)
+            }
+        }
+    }
+}
+
+impl error::Error for WordsearchError {}
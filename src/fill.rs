@@ -0,0 +1,337 @@
+use crate::{WordsearchError, EMPTY_CHAR, MASKED_CHAR};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::slice::Iter;
+use std::str::FromStr;
+
+const LOWERCASE_CHARSET: &str = "abcdefghijklmnopqrstuvwxyz";
+
+/// How the cells not used by a word are filled in.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FillStrategy {
+    /// Letters drawn uniformly from the given alphabet.
+    Alphabet(Vec<char>),
+    /// Letters drawn with the frequency they have in the language.
+    Language(Language),
+    /// Letters drawn with the frequency they have in the words being placed,
+    /// so that the filler gives nothing away.
+    WordLetters,
+}
+
+impl Default for FillStrategy {
+    /// The letters a to z, drawn uniformly.
+    fn default() -> Self {
+        FillStrategy::Alphabet(LOWERCASE_CHARSET.chars().collect())
+    }
+}
+
+/// Languages with a table of letter frequencies for filling grids.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Language {
+    English,
+    French,
+    German,
+    Spanish,
+}
+
+impl Language {
+    pub fn iterator() -> Iter<'static, Language> {
+        static LANGUAGES: [Language; 4] = [
+            Language::English,
+            Language::French,
+            Language::German,
+            Language::Spanish,
+        ];
+        LANGUAGES.iter()
+    }
+
+    /// The frequency of each letter in hundredths of a percent of running text.
+    pub fn letter_frequencies(&self) -> &'static [(char, u32)] {
+        match self {
+            Language::English => &ENGLISH_FREQUENCIES,
+            Language::French => &FRENCH_FREQUENCIES,
+            Language::German => &GERMAN_FREQUENCIES,
+            Language::Spanish => &SPANISH_FREQUENCIES,
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Language::English => "english",
+            Language::French => "french",
+            Language::German => "german",
+            Language::Spanish => "spanish",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Language {
+    type Err = WordsearchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        Language::iterator()
+            .find(|l| l.to_string() == s)
+            .copied()
+            .ok_or(WordsearchError::UnknownLanguage(s))
+    }
+}
+
+/// Draws filler letters for a `FillStrategy`.
+pub(crate) struct Filler {
+    chars: Vec<char>,
+    // Uniform when there are no weights.
+    weights: Option<WeightedIndex<u32>>,
+}
+
+impl Filler {
+    pub(crate) fn new(strategy: &FillStrategy, words: &[&str]) -> Result<Filler, WordsearchError> {
+        let weighted: Vec<(char, u32)> = match strategy {
+            FillStrategy::Alphabet(chars) => {
+                if chars.is_empty() {
+                    return Err(WordsearchError::EmptyAlphabet);
+                }
+
+                if let Some(c) = chars
+                    .iter()
+                    .find(|c| **c == EMPTY_CHAR || **c == MASKED_CHAR || c.is_whitespace())
+                {
+                    return Err(WordsearchError::InvalidFillCharacter(*c));
+                }
+
+                return Ok(Filler {
+                    chars: chars.to_owned(),
+                    weights: None,
+                });
+            }
+            FillStrategy::Language(language) => language.letter_frequencies().to_vec(),
+            FillStrategy::WordLetters => {
+                let mut counts = BTreeMap::new();
+                for c in words.iter().flat_map(|w| w.chars()) {
+                    *counts.entry(c).or_insert(0) += 1;
+                }
+
+                if counts.is_empty() {
+                    return Err(WordsearchError::EmptyAlphabet);
+                }
+
+                counts.into_iter().collect()
+            }
+        };
+
+        Ok(Filler {
+            chars: weighted.iter().map(|(c, _)| *c).collect(),
+            // Every table has at least one letter and no zero weights.
+            weights: Some(WeightedIndex::new(weighted.iter().map(|(_, w)| *w)).unwrap()),
+        })
+    }
+
+    pub(crate) fn random_char<R: Rng + ?Sized>(&self, rng: &mut R) -> char {
+        let idx = match &self.weights {
+            Some(weights) => weights.sample(rng),
+            None => rng.gen_range(0, self.chars.len()),
+        };
+        self.chars[idx]
+    }
+}
+
+// Letter frequencies are taken from https://en.wikipedia.org/wiki/Letter_frequency
+
+static ENGLISH_FREQUENCIES: [(char, u32); 26] = [
+    ('a', 817),
+    ('b', 149),
+    ('c', 278),
+    ('d', 425),
+    ('e', 1270),
+    ('f', 223),
+    ('g', 202),
+    ('h', 609),
+    ('i', 697),
+    ('j', 15),
+    ('k', 77),
+    ('l', 403),
+    ('m', 241),
+    ('n', 675),
+    ('o', 751),
+    ('p', 193),
+    ('q', 10),
+    ('r', 599),
+    ('s', 633),
+    ('t', 906),
+    ('u', 276),
+    ('v', 98),
+    ('w', 236),
+    ('x', 15),
+    ('y', 197),
+    ('z', 7),
+];
+
+static FRENCH_FREQUENCIES: [(char, u32); 39] = [
+    ('a', 764),
+    ('b', 90),
+    ('c', 326),
+    ('d', 367),
+    ('e', 1472),
+    ('f', 107),
+    ('g', 87),
+    ('h', 74),
+    ('i', 753),
+    ('j', 61),
+    ('k', 7),
+    ('l', 546),
+    ('m', 297),
+    ('n', 710),
+    ('o', 580),
+    ('p', 252),
+    ('q', 136),
+    ('r', 669),
+    ('s', 795),
+    ('t', 724),
+    ('u', 631),
+    ('v', 184),
+    ('w', 5),
+    ('x', 43),
+    ('y', 13),
+    ('z', 33),
+    ('à', 49),
+    ('â', 5),
+    ('œ', 2),
+    ('ç', 9),
+    ('è', 27),
+    ('é', 150),
+    ('ê', 22),
+    ('ë', 1),
+    ('î', 5),
+    ('ï', 1),
+    ('ô', 2),
+    ('ù', 6),
+    ('û', 6),
+];
+
+static GERMAN_FREQUENCIES: [(char, u32); 30] = [
+    ('a', 652),
+    ('b', 189),
+    ('c', 273),
+    ('d', 508),
+    ('e', 1640),
+    ('f', 166),
+    ('g', 301),
+    ('h', 458),
+    ('i', 655),
+    ('j', 27),
+    ('k', 142),
+    ('l', 344),
+    ('m', 253),
+    ('n', 978),
+    ('o', 259),
+    ('p', 67),
+    ('q', 2),
+    ('r', 700),
+    ('s', 727),
+    ('t', 615),
+    ('u', 417),
+    ('v', 85),
+    ('w', 192),
+    ('x', 3),
+    ('y', 4),
+    ('z', 113),
+    ('ä', 58),
+    ('ö', 44),
+    ('ß', 31),
+    ('ü', 100),
+];
+
+static SPANISH_FREQUENCIES: [(char, u32); 33] = [
+    ('a', 1153),
+    ('b', 222),
+    ('c', 402),
+    ('d', 501),
+    ('e', 1218),
+    ('f', 69),
+    ('g', 177),
+    ('h', 70),
+    ('i', 625),
+    ('j', 49),
+    ('k', 1),
+    ('l', 497),
+    ('m', 316),
+    ('n', 671),
+    ('o', 868),
+    ('p', 251),
+    ('q', 88),
+    ('r', 687),
+    ('s', 798),
+    ('t', 463),
+    ('u', 293),
+    ('v', 114),
+    ('w', 2),
+    ('x', 22),
+    ('y', 101),
+    ('z', 47),
+    ('á', 50),
+    ('é', 43),
+    ('í', 73),
+    ('ñ', 31),
+    ('ó', 83),
+    ('ú', 17),
+    ('ü', 1),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_filler_draws_from_strategy() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let filler = Filler::new(&FillStrategy::Alphabet(vec!['x', 'y']), &[]).unwrap();
+        for _ in 0..100 {
+            assert!("xy".contains(filler.random_char(&mut rng)));
+        }
+
+        let filler = Filler::new(&FillStrategy::WordLetters, &["aab", "ñ"]).unwrap();
+        let drawn = (0..1000)
+            .map(|_| filler.random_char(&mut rng))
+            .collect::<String>();
+        assert!(drawn.chars().all(|c| "abñ".contains(c)));
+        assert!(drawn.matches('a').count() > drawn.matches('b').count());
+
+        for language in Language::iterator() {
+            let filler = Filler::new(&FillStrategy::Language(*language), &[]).unwrap();
+            let drawn = (0..1000)
+                .map(|_| filler.random_char(&mut rng))
+                .collect::<String>();
+            assert!(drawn.matches('e').count() > drawn.matches('z').count());
+        }
+    }
+
+    #[test]
+    fn test_filler_errors() {
+        assert!(Filler::new(&FillStrategy::Alphabet(vec![]), &[]).is_err());
+        assert_eq!(
+            Filler::new(&FillStrategy::Alphabet(vec!['a', ' ']), &[]).err(),
+            Some(WordsearchError::InvalidFillCharacter(' '))
+        );
+        assert_eq!(
+            Filler::new(&FillStrategy::WordLetters, &[]).err(),
+            Some(WordsearchError::EmptyAlphabet)
+        );
+    }
+
+    #[test]
+    fn test_language_from_str() {
+        for language in Language::iterator() {
+            assert_eq!(language.to_string().parse(), Ok(*language));
+        }
+        assert_eq!("Spanish".parse(), Ok(Language::Spanish));
+        assert!("klingon".parse::<Language>().is_err());
+    }
+}
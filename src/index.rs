@@ -0,0 +1,166 @@
+#[cfg(feature = "parallel")]
+use crate::solver::solve_rows_in_parallel;
+use crate::solver::{find_in_each_ray, validate_solver_words};
+use crate::{Cell, Direction, Grid, WordLocation, WordsearchError};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// A prefix trie built from a word list.
+///
+/// Building the index is the expensive part of solving, so an index can be
+/// reused to solve many grids that share a word list. Rays are abandoned as soon
+/// as they no longer spell the prefix of any word.
+#[derive(Clone, Debug)]
+pub struct WordIndex {
+    nodes: Vec<Node>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Node {
+    children: HashMap<char, usize>,
+    word: Option<String>,
+}
+
+const ROOT: usize = 0;
+
+impl WordIndex {
+    pub fn new(words: &[&str]) -> Result<WordIndex, WordsearchError> {
+        validate_solver_words(words, 1)?;
+
+        let mut index = WordIndex {
+            nodes: vec![Node::default()],
+        };
+
+        for w in words {
+            let mut node = ROOT;
+            for c in w.chars() {
+                node = match index.nodes[node].children.get(&c) {
+                    Some(n) => *n,
+                    None => {
+                        index.nodes.push(Node::default());
+                        let n = index.nodes.len() - 1;
+                        index.nodes[node].children.insert(c, n);
+                        n
+                    }
+                };
+            }
+            index.nodes[node].word = Some(w.to_string());
+        }
+
+        Ok(index)
+    }
+
+    /// Finds the first word along each ray, returning the same results as `solve_grid_naive`.
+    pub fn solve(&self, grid: &Grid) -> Vec<WordLocation> {
+        self.solve_rows(grid, 0..grid.row_count())
+    }
+
+    /// As `solve`, but shares the rows of the grid between threads.
+    #[cfg(feature = "parallel")]
+    pub fn solve_parallel(&self, grid: &Grid) -> Vec<WordLocation> {
+        // Solving with an index cannot fail.
+        solve_rows_in_parallel(grid, |rows| Ok(self.solve_rows(grid, rows))).unwrap()
+    }
+
+    pub(crate) fn solve_rows(&self, grid: &Grid, rows: Range<usize>) -> Vec<WordLocation> {
+        find_in_each_ray(
+            grid,
+            rows,
+            Direction::iterator().as_slice(),
+            |cell, direction| self.find_word_in_direction(cell, direction, grid),
+        )
+    }
+
+    fn find_word_in_direction(
+        &self,
+        start_cell: Cell,
+        direction: &Direction,
+        grid: &Grid,
+    ) -> Option<WordLocation> {
+        let mut node = ROOT;
+
+        for cell in grid.ray(&start_cell, direction) {
+            node = *self.nodes[node].children.get(&grid.value_at_cell(&cell))?;
+
+            if let Some(word) = &self.nodes[node].word {
+                return Some(WordLocation {
+                    word: word.clone(),
+                    start_cell,
+                    end_cell: cell,
+                    direction: direction.to_owned(),
+                });
+            }
+        }
+
+        None
+    }
+
+    /// Finds every word along the ray, not just the first.
+    pub(crate) fn find_all_words_in_direction(
+        &self,
+        start_cell: Cell,
+        direction: &Direction,
+        grid: &Grid,
+    ) -> Vec<WordLocation> {
+        let mut found = Vec::new();
+        let mut node = ROOT;
+
+        for cell in grid.ray(&start_cell, direction) {
+            node = match self.nodes[node].children.get(&grid.value_at_cell(&cell)) {
+                Some(n) => *n,
+                None => break,
+            };
+
+            if let Some(word) = &self.nodes[node].word {
+                found.push(WordLocation {
+                    word: word.clone(),
+                    start_cell,
+                    end_cell: cell,
+                    direction: direction.to_owned(),
+                });
+            }
+        }
+
+        found
+    }
+}
+
+pub fn solve_grid_trie(grid: &Grid, words: &[&str]) -> Result<Vec<WordLocation>, WordsearchError> {
+    Ok(WordIndex::new(words)?.solve(grid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solve_grid_naive;
+
+    #[test]
+    fn test_word_index_prefixes() {
+        let grid = Grid::new(&[vec!['i', 'n', 'i', 't'], vec!['n', 'o', 'o', 'n']]).unwrap();
+        let words = ["init", "in", "noon", "not"];
+
+        let index = WordIndex::new(&words).unwrap();
+        let found = index.solve(&grid);
+
+        assert_eq!(found, solve_grid_naive(&grid, &words).unwrap());
+        assert_eq!(
+            found.iter().map(|w| w.word.as_str()).collect::<Vec<_>>(),
+            vec!["in", "in", "in", "in", "noon", "noon"]
+        );
+
+        // The same index can be reused for another grid.
+        let grid = Grid::new(&[vec!['n', 'o', 't']]).unwrap();
+        assert_eq!(index.solve(&grid), solve_grid_naive(&grid, &words).unwrap());
+    }
+
+    #[test]
+    fn test_word_index_errors() {
+        assert_eq!(
+            WordIndex::new(&["a", ""]).unwrap_err(),
+            WordsearchError::WordTooShort {
+                word: "".to_string(),
+                min: 1
+            }
+        );
+    }
+}
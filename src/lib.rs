@@ -1,43 +1,176 @@
+use fill::Filler;
 use itertools::iproduct;
 use itertools::Itertools;
-use rand::prelude::{SliceRandom, ThreadRng};
-use rand::Rng;
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::cmp::min;
-use std::collections::HashSet;
 use std::fmt;
 use std::slice::Iter;
+use std::str::FromStr;
+
+mod error;
+mod fill;
+mod index;
+mod mask;
+mod normalize;
+mod puzzle;
+mod render;
+mod snake;
+mod solver;
+mod words;
+
+pub use error::WordsearchError;
+pub use fill::{FillStrategy, Language};
+pub use index::{solve_grid_trie, WordIndex};
+pub use mask::{Mask, Shape};
+pub use normalize::{solve_normalized, Normalization};
+pub use puzzle::{Puzzle, PuzzleMetadata, PUZZLE_FORMAT_VERSION};
+pub use render::*;
+pub use snake::{
+    generate_snake_grid_with_options, solve_grid_snake, solve_snake_normalized,
+    GeneratedSnakePuzzle, WordPath,
+};
+pub use solver::*;
+pub use words::{normalize_words, parse_word_list};
 
 const EMPTY_CHAR: char = '_';
-const LOWERCASE_CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const MASKED_CHAR: char = '#';
+const MAX_REFILL_ATTEMPTS: usize = 100;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Cell {
     pub row: usize,
     pub col: usize,
 }
 
+/// Formats the cell as `row,col`.
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.row, self.col)
+    }
+}
+
+/// A rectangular grid of letters, one `char` per cell.
+///
+/// Words are measured and placed in chars, so a letter written with a combining
+/// mark rather than in its precomposed (NFC) form takes up more than one cell.
+///
+/// Cells masked out of a shaped grid hold `#`. No word is placed in or read
+/// through them, and renderers leave them blank.
+///
+/// A grid is `Topology::Bounded` unless it is changed with `with_topology`.
+///
+/// With the `serde` feature, a bounded grid is serialized as a list of rows,
+/// each a string with one char per cell. Other grids are serialized as an
+/// object with the `rows` and the `topology`.
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "SerializedGrid", into = "SerializedGrid")
+)]
 pub struct Grid {
     chars: Vec<Vec<char>>,
+    topology: Topology,
 }
 
 impl Grid {
     pub fn empty(row_count: usize, col_count: usize) -> Grid {
-        Grid::new(&vec![vec![EMPTY_CHAR; col_count]; row_count])
-    }
-
-    pub fn new(chars: &[Vec<char>]) -> Grid {
         Grid {
-            chars: chars.to_owned(),
+            chars: vec![vec![EMPTY_CHAR; col_count]; row_count],
+            topology: Topology::default(),
         }
     }
 
-    pub fn one_word_grid(&self, word: &WordLocation) -> Grid {
-        let mut grid = Grid::empty(self.row_count(), self.col_count());
-        grid.add_word_at_location(word);
+    /// An empty grid the size of the mask, with its masked out cells already in place.
+    pub fn empty_with_mask(mask: &Mask) -> Grid {
+        let mut grid = Grid::empty(mask.row_count(), mask.col_count());
+        for (r, c) in iproduct!(0..mask.row_count(), 0..mask.col_count()) {
+            let cell = Cell { row: r, col: c };
+            if !mask.is_open(&cell) {
+                grid.set_value_at_cell(&cell, MASKED_CHAR);
+            }
+        }
         grid
     }
 
+    /// Returns an error if the rows are not all the same length.
+    pub fn new(chars: &[Vec<char>]) -> Result<Grid, WordsearchError> {
+        if let Some(first) = chars.first() {
+            if let Some((row, r)) = chars
+                .iter()
+                .enumerate()
+                .find(|(_, r)| r.len() != first.len())
+            {
+                return Err(WordsearchError::RaggedGrid {
+                    row,
+                    expected: first.len(),
+                    found: r.len(),
+                });
+            }
+        }
+
+        Ok(Grid {
+            chars: chars.to_owned(),
+            topology: Topology::default(),
+        })
+    }
+
+    /// The same grid, with words running off one edge continuing from the opposite edge
+    /// if the topology is `Toroidal`.
+    pub fn with_topology(self, topology: Topology) -> Grid {
+        Grid { topology, ..self }
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn one_word_grid(&self, word: &WordLocation) -> Result<Grid, WordsearchError> {
+        let mut grid = Grid::empty(self.row_count(), self.col_count()).with_topology(self.topology);
+        grid.add_word_at_location(word)?;
+        Ok(grid)
+    }
+
+    /// A grid of the same size and shape containing only the cells covered by
+    /// the given word locations, e.g. an answer key.
+    pub fn answer_grid(&self, words: &[WordLocation]) -> Result<Grid, WordsearchError> {
+        let mut grid = Grid {
+            chars: self
+                .chars
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|c| {
+                            if *c == MASKED_CHAR {
+                                MASKED_CHAR
+                            } else {
+                                EMPTY_CHAR
+                            }
+                        })
+                        .collect()
+                })
+                .collect(),
+            topology: self.topology,
+        };
+        for w in words {
+            let cells = self
+                .word_cells(w)
+                .ok_or_else(|| WordsearchError::LocationOutOfBounds {
+                    word: w.word.clone(),
+                })?;
+
+            for c in cells {
+                grid.set_value_at_cell(&c, self.value_at_cell(&c));
+            }
+        }
+        Ok(grid)
+    }
+
     pub fn row_count(&self) -> usize {
         self.chars.len()
     }
@@ -51,35 +184,71 @@ impl Grid {
     }
 
     // Will clobber any existing chars
-    // Panics if word does not fit
-    fn add_word_at_location(&mut self, wl: &WordLocation) {
-        assert!(
-            self.cells_remaining_in_direction(&wl.start_cell, &wl.direction) >= wl.word.len() - 1,
-            "Not enough cells remain to place word: {}",
-            wl.word
-        );
+    fn add_word_at_location(&mut self, wl: &WordLocation) -> Result<(), WordsearchError> {
+        if wl.start_cell.row >= self.row_count()
+            || wl.start_cell.col >= self.col_count()
+            || self.cells_remaining_in_direction(&wl.start_cell, &wl.direction) + 1
+                < wl.word.chars().count()
+        {
+            return Err(WordsearchError::LocationOutOfBounds {
+                word: wl.word.clone(),
+            });
+        }
 
         let mut cell = wl.start_cell;
         for (i, c) in wl.word.chars().enumerate() {
+            if i > 0 {
+                // We have already validated that there are enough cells to place the word.
+                cell = self.neighbor(&cell, &wl.direction).unwrap();
+            }
+
             self.set_value_at_cell(&cell, c);
+        }
 
-            if i == wl.word.len() - 1 {
-                return;
-            }
+        Ok(())
+    }
 
-            cell = self.next_cell_in_direction(&cell, &wl.direction).unwrap(); // We have already validated that there are enough cells to place the word.
+    // The cells from the start to the end of the location, or None if the end
+    // cannot be reached from the start within the grid.
+    fn word_cells(&self, wl: &WordLocation) -> Option<Vec<Cell>> {
+        let mut cells = vec![];
+        for cell in self.ray(&wl.start_cell, &wl.direction) {
+            cells.push(cell);
+            if cell == wl.end_cell {
+                return Some(cells);
+            }
         }
+        None
+    }
+
+    /// The start cell followed by each cell after it in the direction, as far as
+    /// a word could run: to the edge of a bounded grid, or to just before the ray
+    /// would return to the start of a toroidal one. Empty if the start is not in
+    /// the grid.
+    pub fn ray(&self, start: &Cell, direction: &Direction) -> impl Iterator<Item = Cell> + '_ {
+        let direction = *direction;
+        let length = if self.contains(start) {
+            self.cells_remaining_in_direction(start, &direction) + 1
+        } else {
+            0
+        };
+
+        std::iter::successors(Some(*start), move |c| self.neighbor(c, &direction)).take(length)
+    }
+
+    fn contains(&self, cell: &Cell) -> bool {
+        cell.row < self.row_count() && cell.col < self.col_count()
     }
 
     fn set_value_at_cell(&mut self, cell: &Cell, val: char) {
         self.chars[cell.row][cell.col] = val;
     }
 
-    fn fill_empty_cells_with_chars(&mut self, rng: &mut ThreadRng) {
+    fn fill_empty_cells_with_chars<R: Rng + ?Sized>(&mut self, filler: &Filler, rng: &mut R) {
         for r in 0..self.chars.len() {
             for c in 0..self.chars[r].len() {
                 if self.chars[r][c] == EMPTY_CHAR {
-                    self.chars[r][c] = random_char(rng);
+                    self.chars[r][c] = filler.random_char(rng);
                 }
             }
         }
@@ -90,11 +259,38 @@ impl Grid {
         self.chars[cell.row][cell.col]
     }
 
-    fn next_cell_in_direction(&self, cell: &Cell, direction: &Direction) -> Option<Cell> {
+    /// The cell next to the cell in the direction. In a toroidal grid, this wraps
+    /// around to the opposite edge. None at the edge of a bounded grid, or if the
+    /// cell is not in the grid.
+    ///
+    /// A toroidal grid with a single row or column has no neighbours across it,
+    /// so that a word never reaches its own first cell again. Nor has it any
+    /// diagonal neighbours, as they would be the same cells as along the row
+    /// or column.
+    pub fn neighbor(&self, cell: &Cell, direction: &Direction) -> Option<Cell> {
         if self.cells_remaining_in_direction(cell, direction) == 0 {
             return None;
         }
 
+        if self.topology == Topology::Toroidal {
+            let (rows, cols) = (self.row_count(), self.col_count());
+            let (row, col) = match direction {
+                Direction::Up => (cell.row + rows - 1, cell.col),
+                Direction::UpRight => (cell.row + rows - 1, cell.col + 1),
+                Direction::Right => (cell.row, cell.col + 1),
+                Direction::DownRight => (cell.row + 1, cell.col + 1),
+                Direction::Down => (cell.row + 1, cell.col),
+                Direction::DownLeft => (cell.row + 1, cell.col + cols - 1),
+                Direction::Left => (cell.row, cell.col + cols - 1),
+                Direction::UpLeft => (cell.row + rows - 1, cell.col + cols - 1),
+            };
+
+            return Some(Cell {
+                row: row % rows,
+                col: col % cols,
+            });
+        }
+
         let (row, col) = match direction {
             Direction::Up => (cell.row - 1, cell.col),
             Direction::UpRight => (cell.row - 1, cell.col + 1),
@@ -109,7 +305,27 @@ impl Grid {
         Some(Cell { row, col })
     }
 
-    fn cells_remaining_in_direction(&self, cell: &Cell, direction: &Direction) -> usize {
+    /// How many cells follow the cell in the direction before the edge of a
+    /// bounded grid, or before returning to the cell in a toroidal one. Zero if
+    /// the cell is not in the grid.
+    pub fn cells_remaining_in_direction(&self, cell: &Cell, direction: &Direction) -> usize {
+        if !self.contains(cell) {
+            return 0;
+        }
+
+        if self.topology == Topology::Toroidal {
+            // Stop before the word would reach its first cell again and overlap itself.
+            let period = match direction {
+                Direction::Left | Direction::Right => self.col_count(),
+                Direction::Up | Direction::Down => self.row_count(),
+                // Wrapping a diagonal around a single row or column would read the
+                // same cells as the row or column itself.
+                _ if self.row_count() == 1 || self.col_count() == 1 => 0,
+                _ => lcm(self.row_count(), self.col_count()),
+            };
+            return period.saturating_sub(1);
+        }
+
         let rows_remaining = match direction {
             Direction::UpLeft | Direction::Up | Direction::UpRight => cell.row,
             Direction::DownLeft | Direction::Down | Direction::DownRight => {
@@ -143,7 +359,100 @@ impl fmt::Display for Grid {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd)]
+/// Parses a grid from its `Display` format, or from rows of unspaced letters.
+///
+/// Whitespace within a line is ignored, as are blank lines. Line and column
+/// numbers in errors count from one.
+impl FromStr for Grid {
+    type Err = WordsearchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars: Vec<Vec<char>> = Vec::new();
+
+        for (line_index, line) in s.lines().enumerate() {
+            let letters = line
+                .chars()
+                .enumerate()
+                .filter(|(_, c)| !c.is_whitespace())
+                .collect::<Vec<(usize, char)>>();
+
+            if letters.is_empty() {
+                continue;
+            }
+
+            if let Some(first) = chars.first() {
+                if letters.len() != first.len() {
+                    // Point at the first extra letter, or just past the last letter if there are too few.
+                    let column = match letters.get(first.len()) {
+                        Some((i, _)) => *i,
+                        None => letters[letters.len() - 1].0 + 1,
+                    };
+
+                    return Err(WordsearchError::RaggedLine {
+                        line: line_index + 1,
+                        column: column + 1,
+                        expected: first.len(),
+                        found: letters.len(),
+                    });
+                }
+            }
+
+            chars.push(letters.into_iter().map(|(_, c)| c).collect());
+        }
+
+        if chars.is_empty() {
+            return Err(WordsearchError::EmptyGrid);
+        }
+
+        Grid::new(&chars)
+    }
+}
+
+// How a grid is serialized: as just its rows when it is bounded, so that
+// the usual grid is a plain list of strings.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum SerializedGrid {
+    Rows(Vec<String>),
+    WithTopology {
+        rows: Vec<String>,
+        topology: Topology,
+    },
+}
+
+#[cfg(feature = "serde")]
+impl From<Grid> for SerializedGrid {
+    fn from(grid: Grid) -> Self {
+        let rows = grid
+            .chars
+            .into_iter()
+            .map(|row| row.into_iter().collect())
+            .collect();
+
+        match grid.topology {
+            Topology::Bounded => SerializedGrid::Rows(rows),
+            topology => SerializedGrid::WithTopology { rows, topology },
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<SerializedGrid> for Grid {
+    type Error = WordsearchError;
+
+    fn try_from(serialized: SerializedGrid) -> Result<Self, Self::Error> {
+        let (rows, topology) = match serialized {
+            SerializedGrid::Rows(rows) => (rows, Topology::Bounded),
+            SerializedGrid::WithTopology { rows, topology } => (rows, topology),
+        };
+        let chars = rows.iter().map(|r| r.chars().collect()).collect::<Vec<_>>();
+        Ok(Grid::new(&chars)?.with_topology(topology))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct WordLocation {
     pub word: String,
     pub start_cell: Cell,
@@ -152,6 +461,29 @@ pub struct WordLocation {
 }
 
 impl WordLocation {
+    /// The cells of the word in order, from its start to its end in its direction.
+    ///
+    /// The grid is needed to know where a word wraps around the edges of a
+    /// toroidal grid. There are no cells if the end cannot be reached from the
+    /// start in the grid.
+    pub fn cells(&self, grid: &Grid) -> impl Iterator<Item = Cell> {
+        grid.word_cells(self).unwrap_or_default().into_iter()
+    }
+
+    // Whether both locations are the same word in the same cells, read in either direction.
+    // In a toroidal grid, the same start and end cells can be joined in more than one
+    // direction, so the directions must match too.
+    fn covers_same_cells(&self, other: &WordLocation) -> bool {
+        let one_cell = self.start_cell == self.end_cell;
+        self.word == other.word
+            && ((self.start_cell == other.start_cell
+                && self.end_cell == other.end_cell
+                && (one_cell || self.direction == other.direction))
+                || (self.start_cell == other.end_cell
+                    && self.end_cell == other.start_cell
+                    && (one_cell || self.direction == opposite_direction(&other.direction))))
+    }
+
     fn into_reversed_location(self) -> WordLocation {
         WordLocation {
             word: self.word.chars().rev().collect::<String>(),
@@ -162,7 +494,13 @@ impl WordLocation {
     }
 }
 
+/// With the `serde` feature, directions are serialized by their `Display` names.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "kebab-case")
+)]
 pub enum Direction {
     Up,
     UpRight,
@@ -190,45 +528,318 @@ impl Direction {
     }
 }
 
-pub fn generate_grid(rows: usize, cols: usize, words: &[&str]) -> Option<Grid> {
-    if words.is_empty() {
-        return None;
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Direction::Up => "up",
+            Direction::UpRight => "up-right",
+            Direction::Right => "right",
+            Direction::DownRight => "down-right",
+            Direction::Down => "down",
+            Direction::DownLeft => "down-left",
+            Direction::Left => "left",
+            Direction::UpLeft => "up-left",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Direction {
+    type Err = WordsearchError;
+
+    /// Parses the names produced by `Display`, e.g. `up-right`, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        Direction::iterator()
+            .find(|d| d.to_string() == s)
+            .copied()
+            .ok_or(WordsearchError::UnknownDirection(s))
+    }
+}
+
+/// How the edges of a grid join up.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Topology {
+    /// Words stop at the edges of the grid.
+    #[default]
+    Bounded,
+    /// Words running off one edge continue from the opposite edge, as if the
+    /// grid were drawn on a torus. A word never wraps far enough to reach its
+    /// own first cell again.
+    Toroidal,
+}
+
+impl Topology {
+    pub fn iterator() -> Iter<'static, Topology> {
+        static TOPOLOGIES: [Topology; 2] = [Topology::Bounded, Topology::Toroidal];
+        TOPOLOGIES.iter()
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Topology::Bounded => write!(f, "bounded"),
+            Topology::Toroidal => write!(f, "toroidal"),
+        }
+    }
+}
+
+impl FromStr for Topology {
+    type Err = WordsearchError;
+
+    /// Parses the names produced by `Display`, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        Topology::iterator()
+            .find(|t| t.to_string() == s)
+            .copied()
+            .ok_or(WordsearchError::UnknownTopology(s))
+    }
+}
+
+/// Presets for the directions words may be placed in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Difficulty {
+    /// Words read left-to-right or top-to-bottom.
+    Easy,
+    /// As `Easy`, plus the two diagonals that read left-to-right.
+    Medium,
+    /// All eight directions, including backwards.
+    Hard,
+}
+
+impl Difficulty {
+    pub fn directions(&self) -> Vec<Direction> {
+        match self {
+            Difficulty::Easy => vec![Direction::Right, Direction::Down],
+            Difficulty::Medium => vec![
+                Direction::UpRight,
+                Direction::Right,
+                Direction::DownRight,
+                Direction::Down,
+            ],
+            Difficulty::Hard => Direction::iterator().copied().collect(),
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = WordsearchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            other => Err(WordsearchError::UnknownDifficulty(other.to_string())),
+        }
+    }
+}
+
+/// Settings controlling how words are placed in a generated grid.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GenerationOptions {
+    /// The directions words may be placed in.
+    pub directions: Vec<Direction>,
+    /// Whether to verify that each word occurs exactly once in the filled grid,
+    /// re-filling random characters that spell out a word a second time.
+    pub ensure_unique: bool,
+    /// How the cells not used by a word are filled in.
+    pub fill: FillStrategy,
+    /// The shape of the grid. Masked out cells are never used for a word or
+    /// filled in. The mask must be the same size as the grid.
+    pub mask: Option<Mask>,
+    /// Whether words may wrap around the edges of the grid.
+    pub topology: Topology,
+    /// Words placed at fixed locations before any others, and never moved.
+    /// They are part of the puzzle whether or not they are in the word list,
+    /// and may be in any direction.
+    pub pinned: Vec<WordLocation>,
+    /// A grid to start from, the same size as the grid. Its letters are kept,
+    /// and words may only cross them where the letters match. Cells that are
+    /// empty (`_`) are free, and any mask is applied on top.
+    pub template: Option<Grid>,
+}
+
+impl GenerationOptions {
+    pub fn with_directions(directions: &[Direction]) -> GenerationOptions {
+        GenerationOptions {
+            directions: directions.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// The length of the longest word that fits in a grid of the given size in
+    /// any of the allowed directions. With a mask, this is the longest line of
+    /// open cells in the mask.
+    pub fn max_word_length(&self, rows: usize, cols: usize) -> usize {
+        match &self.mask {
+            None => self
+                .directions
+                .iter()
+                .map(|d| match (d, self.topology) {
+                    (Direction::Left, _) | (Direction::Right, _) => cols,
+                    (Direction::Up, _) | (Direction::Down, _) => rows,
+                    (_, Topology::Bounded) => min(rows, cols),
+                    (_, Topology::Toroidal) if rows == 1 || cols == 1 => 0,
+                    (_, Topology::Toroidal) => lcm(rows, cols),
+                })
+                .max()
+                .unwrap_or(0),
+            Some(mask) => {
+                let grid = Grid::empty_with_mask(mask).with_topology(self.topology);
+                iproduct!(0..grid.row_count(), 0..grid.col_count())
+                    .map(|(r, c)| Cell { row: r, col: c })
+                    .filter(|c| mask.is_open(c))
+                    .flat_map(|c| self.directions.iter().map(move |d| (c, *d)))
+                    .map(|(c, d)| grid.ray(&c, &d).take_while(|c| mask.is_open(c)).count())
+                    .max()
+                    .unwrap_or(0)
+            }
+        }
+    }
+}
+
+impl Default for GenerationOptions {
+    fn default() -> Self {
+        GenerationOptions {
+            directions: Difficulty::Hard.directions(),
+            ensure_unique: true,
+            fill: FillStrategy::default(),
+            mask: None,
+            topology: Topology::default(),
+            pinned: Vec::new(),
+            template: None,
+        }
+    }
+}
+
+impl From<Difficulty> for GenerationOptions {
+    fn from(difficulty: Difficulty) -> Self {
+        GenerationOptions {
+            directions: difficulty.directions(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A generated wordsearch along with the location of every placed word.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GeneratedPuzzle {
+    /// The puzzle, with every cell not used by a word filled with a random character.
+    pub grid: Grid,
+    /// The placed words and the letters of any template only; every other
+    /// cell is empty or masked out.
+    pub placement_grid: Grid,
+    /// The answer key, including any pinned words, sorted in the same order as
+    /// the solvers return their results.
+    pub placements: Vec<WordLocation>,
+}
+
+/// Generates a grid using the thread-local random number generator.
+pub fn generate_grid(
+    rows: usize,
+    cols: usize,
+    words: &[&str],
+) -> Result<GeneratedPuzzle, WordsearchError> {
+    generate_grid_with_rng(rows, cols, words, &mut rand::thread_rng())
+}
+
+/// Generates a grid from a seed.
+///
+/// For a given version of this crate, the same seed, size and word list
+/// always produce the same grid.
+pub fn generate_grid_from_seed(
+    rows: usize,
+    cols: usize,
+    words: &[&str],
+    seed: u64,
+) -> Result<GeneratedPuzzle, WordsearchError> {
+    generate_grid_with_rng(rows, cols, words, &mut StdRng::seed_from_u64(seed))
+}
+
+/// Generates a grid using the provided random number generator.
+///
+/// All randomness (word placement and filler characters) is drawn from `rng`,
+/// so a deterministic generator yields a deterministic grid.
+pub fn generate_grid_with_rng<R: Rng + ?Sized>(
+    rows: usize,
+    cols: usize,
+    words: &[&str],
+    rng: &mut R,
+) -> Result<GeneratedPuzzle, WordsearchError> {
+    generate_grid_with_options(rows, cols, words, &GenerationOptions::default(), rng)
+}
+
+/// Generates a grid using the provided options and random number generator.
+pub fn generate_grid_with_options<R: Rng + ?Sized>(
+    rows: usize,
+    cols: usize,
+    words: &[&str],
+    options: &GenerationOptions,
+    rng: &mut R,
+) -> Result<GeneratedPuzzle, WordsearchError> {
+    let (all_words, unpinned_words) = split_pinned_words(words, options);
+
+    validate_generation_words(
+        rows,
+        cols,
+        &all_words,
+        options,
+        options.max_word_length(rows, cols),
+    )?;
+
+    if options.ensure_unique {
+        validate_unique_words(&all_words)?;
     }
 
-    let mut rng = rand::thread_rng();
+    let filler = Filler::new(&options.fill, &all_words)?;
+    let start_grid = starting_grid(rows, cols, options)?;
 
-    let mut word_list = words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+    let mut word_list = unpinned_words
+        .iter()
+        .map(|w| w.to_string())
+        .collect::<Vec<_>>();
 
     // sort word list by longest words first to fit faster.
-    word_list.sort_by_key(|a| a.len());
+    word_list.sort_by_key(|a| a.chars().count());
 
     // reverse word list so we can push/pop easily and yet still preserve initial ordering
     word_list.reverse();
 
     let cells = iproduct!(0..rows, 0..cols)
         .map(|(r, c)| Cell { row: r, col: c })
+        .filter(|c| options.mask.as_ref().map_or(true, |m| m.is_open(c)))
         .collect::<Vec<_>>();
 
-    let directions = Direction::iterator()
-        .map(|d| d.to_owned())
-        .collect::<Vec<_>>();
+    let mut directions = options.directions.clone();
+    directions.sort();
+    directions.dedup();
 
     struct StackEntry {
         grid: Grid,
+        placements: Vec<WordLocation>,
         word: String,
         remaining_possible_directions: Vec<Direction>,
         remaining_possible_cells: Vec<Cell>,
     }
 
     impl StackEntry {
-        /// Shuffles cells and directions with provided ThreadRng
-        /// Deliberately takes ownership of provided grid and word
-        fn new_with_shuffle(
+        /// Shuffles cells and directions with provided Rng
+        /// Deliberately takes ownership of provided grid, placements and word
+        fn new_with_shuffle<R: Rng + ?Sized>(
             grid: Grid,
+            placements: Vec<WordLocation>,
             word: String,
             cells: &[Cell],
             directions: &[Direction],
-            rng: &mut ThreadRng,
+            rng: &mut R,
         ) -> Self {
             let mut ps = cells.to_vec();
             ps.shuffle(rng);
@@ -237,6 +848,7 @@ pub fn generate_grid(rows: usize, cols: usize, words: &[&str]) -> Option<Grid> {
 
             StackEntry {
                 grid,
+                placements,
                 word,
                 remaining_possible_directions: ds,
                 remaining_possible_cells: ps,
@@ -244,20 +856,27 @@ pub fn generate_grid(rows: usize, cols: usize, words: &[&str]) -> Option<Grid> {
         }
     }
 
+    // If every word is pinned, there is nothing left to place.
+    let first_word = match word_list.pop() {
+        Some(w) => w,
+        None => {
+            return fill_placed_grid(start_grid, options.pinned.clone(), &filler, options, rng)
+                .ok_or(WordsearchError::Unplaceable)
+        }
+    };
+
     let mut stack = vec![StackEntry::new_with_shuffle(
-        Grid::empty(rows, cols),
-        word_list.pop().unwrap(), // We know there is at least one word.
+        start_grid,
+        options.pinned.clone(),
+        first_word,
         cells.as_slice(),
         directions.as_slice(),
-        &mut rng,
+        rng,
     )];
 
     loop {
         // Get the current stack item without popping it off the stac.
-        let mut current = match stack.last_mut() {
-            Some(c) => c,
-            None => return None,
-        };
+        let current = stack.last_mut().ok_or(WordsearchError::Unplaceable)?;
 
         // Get the next direction to try
         let direction = match current.remaining_possible_directions.pop() {
@@ -268,7 +887,7 @@ pub fn generate_grid(rows: usize, cols: usize, words: &[&str]) -> Option<Grid> {
                 current.remaining_possible_cells.pop();
 
                 let mut ds = directions.clone();
-                ds.shuffle(&mut rng);
+                ds.shuffle(rng);
                 current.remaining_possible_directions = ds;
                 current.remaining_possible_directions.pop().unwrap() // we just refreshed the list so we know we can pop.
             }
@@ -279,24 +898,31 @@ pub fn generate_grid(rows: usize, cols: usize, words: &[&str]) -> Option<Grid> {
         match current.remaining_possible_cells.last() {
             Some(p) => {
                 // If we failed to place the word then we will default to looping again.
-                if let Some(mut grid) =
+                if let Some((grid, placement)) =
                     place_word_at_cell(&current.grid, p, &direction, &current.word)
                 {
+                    let mut placements = current.placements.clone();
+                    placements.push(placement);
+
                     // Given we placed the word, then
                     // if there are more words to try then create a new item on the stack.
                     // if there are no more words then we are done.
                     if let Some(w) = word_list.pop() {
                         stack.push(StackEntry::new_with_shuffle(
                             grid,
+                            placements,
                             w,
                             &cells,
                             &directions,
-                            &mut rng,
+                            rng,
                         ));
-                    } else {
-                        grid.fill_empty_cells_with_chars(&mut rng);
-                        return Some(grid);
+                    } else if let Some(puzzle) =
+                        fill_placed_grid(grid, placements, &filler, options, rng)
+                    {
+                        return Ok(puzzle);
                     }
+                    // If the placed words themselves spell out a word a second time then
+                    // we default to looping again to try the last word elsewhere.
                 }
             }
             None => {
@@ -311,343 +937,297 @@ pub fn generate_grid(rows: usize, cols: usize, words: &[&str]) -> Option<Grid> {
     }
 }
 
-fn place_word_at_cell(
-    grid: &Grid,
-    start_cell: &Cell,
-    direction: &Direction,
-    word: &str,
-) -> Option<Grid> {
-    // +1 to account for the current cell.
-    if grid.cells_remaining_in_direction(start_cell, direction) + 1 < word.len() {
+// Fills the cells around the placed words. Returns None if the placed words
+// themselves spell out a word a second time.
+fn fill_placed_grid<R: Rng + ?Sized>(
+    placement_grid: Grid,
+    mut placements: Vec<WordLocation>,
+    filler: &Filler,
+    options: &GenerationOptions,
+    rng: &mut R,
+) -> Option<GeneratedPuzzle> {
+    let mut grid = placement_grid.clone();
+    grid.fill_empty_cells_with_chars(filler, rng);
+
+    if options.ensure_unique
+        && !refill_duplicates(&mut grid, &placement_grid, &placements, filler, rng)
+    {
         return None;
     }
 
-    let mut cell: Cell = start_cell.to_owned();
-
-    for (i, c) in word.chars().enumerate() {
-        let char_at_cell = grid.value_at_cell(&cell);
-
-        if char_at_cell != EMPTY_CHAR && char_at_cell != c {
-            return None;
-        }
-
-        let remaining_chars = word.len() - i;
-        if remaining_chars > 1 {
-            cell = grid.next_cell_in_direction(&cell, direction)?;
-        }
-    }
-
-    let mut g = grid.clone();
-
-    g.add_word_at_location(&WordLocation {
-        word: word.to_owned(),
-        start_cell: *start_cell,
-        end_cell: cell,
-        direction: *direction,
-    });
-
-    Some(g)
+    placements.sort();
+    Some(GeneratedPuzzle {
+        grid,
+        placement_grid,
+        placements,
+    })
 }
 
-fn random_char(rng: &mut ThreadRng) -> char {
-    let idx = rng.gen_range(0, LOWERCASE_CHARSET.len());
-    LOWERCASE_CHARSET[idx] as char
-}
-
-pub fn solve_grid_reverse_hash_first_two_letters(grid: &Grid, words: &[&str]) -> Vec<WordLocation> {
-    let mut all_words = words.to_vec();
-    let reverse_words = words
+// All of the words in the puzzle, pinned words first, and the words that are
+// left to place.
+fn split_pinned_words<'a>(
+    words: &[&'a str],
+    options: &'a GenerationOptions,
+) -> (Vec<&'a str>, Vec<&'a str>) {
+    let pinned = options
+        .pinned
         .iter()
-        .map(|w| w.chars().rev().collect::<String>())
+        .map(|p| p.word.as_str())
+        .collect::<Vec<_>>();
+    let unpinned = words
+        .iter()
+        .filter(|w| !pinned.contains(w))
+        .copied()
         .collect::<Vec<_>>();
 
-    all_words.extend(
-        &reverse_words
-            .iter()
-            .map(|w| w.as_str())
-            .collect::<Vec<&str>>(),
-    );
-
-    let directions = [
-        Direction::UpRight,
-        Direction::Right,
-        Direction::DownRight,
-        Direction::Down,
-    ];
+    (pinned.iter().chain(&unpinned).copied().collect(), unpinned)
+}
 
-    let mut hashed: HashSet<Vec<char>> = HashSet::new();
-    for w in all_words.iter() {
-        let mut chars = w.chars();
-        hashed.insert(vec![chars.next().unwrap(), chars.next().unwrap()]); // We expect that all words are at least two chars long.
+// The grid that words are placed into: the template or an empty grid, with
+// the mask applied and the pinned words added.
+fn starting_grid(
+    rows: usize,
+    cols: usize,
+    options: &GenerationOptions,
+) -> Result<Grid, WordsearchError> {
+    let mut grid = match &options.template {
+        Some(template) => {
+            if (template.row_count(), template.col_count()) != (rows, cols) {
+                return Err(WordsearchError::TemplateSizeMismatch {
+                    rows,
+                    cols,
+                    template_rows: template.row_count(),
+                    template_cols: template.col_count(),
+                });
+            }
+            template.clone()
+        }
+        None => Grid::empty(rows, cols),
     }
+    .with_topology(options.topology);
 
-    let mut word_locations = Vec::new();
-
-    for (row, col, direction) in
-        iproduct!(0..grid.row_count(), 0..grid.col_count(), directions.iter())
-    {
-        let cell = Cell { row, col };
-        if let Some(found) =
-            find_word_in_direction_hash(vec![cell], direction, grid, &all_words, &hashed, 2)
-        {
-            let mut w = WordLocation {
-                word: found.word,
-                start_cell: found.start_cell,
-                end_cell: found.end_cell,
-                direction: found.direction,
-            };
-
-            // If the originally-provided list of words does not contain the found word,
-            // the found word must be reversed.
-            if !words.contains(&w.word.as_str()) {
-                w = w.into_reversed_location();
+    if let Some(mask) = &options.mask {
+        for (row, col) in iproduct!(0..rows, 0..cols) {
+            let cell = Cell { row, col };
+            if !mask.is_open(&cell) {
+                grid.set_value_at_cell(&cell, MASKED_CHAR);
             }
+        }
+    }
 
-            word_locations.push(w);
+    for pin in &options.pinned {
+        let cells = grid
+            .word_cells(pin)
+            .filter(|cells| cells.len() == pin.word.chars().count())
+            .ok_or_else(|| WordsearchError::LocationOutOfBounds {
+                word: pin.word.clone(),
+            })?;
+
+        for (cell, letter) in cells.iter().zip(pin.word.chars()) {
+            let existing = grid.value_at_cell(cell);
+            if existing != EMPTY_CHAR && existing != letter {
+                return Err(WordsearchError::PinConflict {
+                    word: pin.word.clone(),
+                    cell: *cell,
+                    letter,
+                    existing,
+                });
+            }
+            grid.set_value_at_cell(cell, letter);
         }
     }
 
-    word_locations.sort();
-    word_locations
+    Ok(grid)
 }
 
-pub fn solve_grid_reverse_hash_first_letter(grid: &Grid, words: &[&str]) -> Vec<WordLocation> {
-    let mut all_words = words.to_vec();
-    let reverse_words = words
-        .iter()
-        .map(|w| w.chars().rev().collect::<String>())
-        .collect::<Vec<_>>();
-
-    all_words.extend(
-        &reverse_words
-            .iter()
-            .map(|w| w.as_str())
-            .collect::<Vec<&str>>(),
-    );
-
-    let directions = [
-        Direction::UpRight,
-        Direction::Right,
-        Direction::DownRight,
-        Direction::Down,
-    ];
-
-    let mut hashed: HashSet<char> = HashSet::new();
-    for w in all_words.iter() {
-        hashed.insert(w.chars().next().unwrap()); // We expect that all words are at least one char long.
+// Checks the words and options before generating. `max` is the length of the
+// longest word that fits in the grid.
+fn validate_generation_words(
+    rows: usize,
+    cols: usize,
+    words: &[&str],
+    options: &GenerationOptions,
+    max: usize,
+) -> Result<(), WordsearchError> {
+    if words.is_empty() {
+        return Err(WordsearchError::EmptyWordList);
     }
 
-    let mut word_locations = Vec::new();
-
-    for (row, col, direction) in
-        iproduct!(0..grid.row_count(), 0..grid.col_count(), directions.iter())
-    {
-        let cell = Cell { row, col };
-        if hashed.contains(&grid.value_at_cell(&cell)) {
-            let cell = Cell { row, col };
-            if let Some(found) = find_word_in_direction(vec![cell], direction, grid, &all_words) {
-                let mut w = WordLocation {
-                    word: found.word,
-                    start_cell: found.start_cell,
-                    end_cell: found.end_cell,
-                    direction: found.direction,
-                };
-
-                // If the originally-provided list of words does not contain the found word,
-                // the found word must be reversed.
-                if !words.contains(&w.word.as_str()) {
-                    w = w.into_reversed_location();
-                }
+    if options.directions.is_empty() {
+        return Err(WordsearchError::NoDirections);
+    }
 
-                word_locations.push(w);
-            }
+    if let Some(mask) = &options.mask {
+        if (mask.row_count(), mask.col_count()) != (rows, cols) {
+            return Err(WordsearchError::MaskSizeMismatch {
+                rows,
+                cols,
+                mask_rows: mask.row_count(),
+                mask_cols: mask.col_count(),
+            });
         }
     }
 
-    word_locations.sort();
-    word_locations
-}
-
-pub fn solve_grid_hash_first_letter(grid: &Grid, words: &[&str]) -> Vec<WordLocation> {
-    let mut hashed: HashSet<char> = HashSet::new();
-    for w in words.iter() {
-        hashed.insert(w.chars().next().unwrap()); // We expect that all words are at least one char long.
-    }
+    for w in words {
+        if w.is_empty() {
+            return Err(WordsearchError::WordTooShort {
+                word: w.to_string(),
+                min: 1,
+            });
+        }
 
-    let mut word_locations = Vec::new();
+        if let Some(c) = w
+            .chars()
+            .find(|c| *c == EMPTY_CHAR || *c == MASKED_CHAR || c.is_whitespace())
+        {
+            return Err(WordsearchError::InvalidCharacter {
+                word: w.to_string(),
+                character: c,
+            });
+        }
 
-    for (row, col, direction) in iproduct!(
-        0..grid.row_count(),
-        0..grid.col_count(),
-        Direction::iterator()
-    ) {
-        let cell = Cell { row, col };
-        if hashed.contains(&grid.value_at_cell(&cell)) {
-            if let Some(found) = find_word_in_direction(vec![cell], direction, grid, words) {
-                word_locations.push(WordLocation {
-                    word: found.word,
-                    start_cell: found.start_cell,
-                    end_cell: found.end_cell,
-                    direction: found.direction,
-                });
-            }
+        // Pinned words are already in place, whatever their length.
+        if w.chars().count() > max && !options.pinned.iter().any(|p| p.word == *w) {
+            return Err(WordsearchError::WordTooLong {
+                word: w.to_string(),
+                max,
+            });
         }
     }
 
-    word_locations.sort();
-    word_locations
+    Ok(())
 }
 
-pub fn solve_grid_naive(grid: &Grid, words: &[&str]) -> Vec<WordLocation> {
-    let mut word_locations = Vec::new();
+// A word within another word (or its reverse) will always occur at least twice.
+fn validate_unique_words(words: &[&str]) -> Result<(), WordsearchError> {
+    for (i, w) in words.iter().enumerate() {
+        let reversed = w.chars().rev().collect::<String>();
 
-    for (row, col, direction) in iproduct!(
-        0..grid.row_count(),
-        0..grid.col_count(),
-        Direction::iterator()
-    ) {
-        let cell = Cell { row, col };
-        if let Some(found) = find_word_in_direction(vec![cell], direction, grid, words) {
-            word_locations.push(WordLocation {
-                word: found.word,
-                start_cell: found.start_cell,
-                end_cell: found.end_cell,
-                direction: found.direction,
-            });
+        for (j, containing) in words.iter().enumerate() {
+            if i != j && (containing.contains(w) || containing.contains(&reversed)) {
+                return Err(WordsearchError::WordWithinWord {
+                    word: w.to_string(),
+                    containing: containing.to_string(),
+                });
+            }
         }
     }
 
-    word_locations.sort();
-    word_locations
+    Ok(())
 }
 
-pub fn solve_grid_reverse_words(grid: &Grid, words: &[&str]) -> Vec<WordLocation> {
-    let mut all_words = words.to_vec();
-    let reverse_words = words
+/// Re-fills the random characters of any extra occurrences of the placed words
+/// until each word occurs exactly once.
+/// Returns false if that could not be achieved, which is always the case when an
+/// extra occurrence is made up entirely of placed characters.
+fn refill_duplicates<R: Rng + ?Sized>(
+    grid: &mut Grid,
+    placement_grid: &Grid,
+    placements: &[WordLocation],
+    filler: &Filler,
+    rng: &mut R,
+) -> bool {
+    let words = placements
         .iter()
-        .map(|w| w.chars().rev().collect::<String>())
+        .map(|p| p.word.as_str())
         .collect::<Vec<_>>();
 
-    all_words.extend(
-        &reverse_words
-            .iter()
-            .map(|w| w.as_str())
-            .collect::<Vec<&str>>(),
-    );
-
-    let mut word_locations = Vec::new();
+    for _ in 0..MAX_REFILL_ATTEMPTS {
+        let duplicates = solve_grid_all_occurrences(grid, &words)
+            .unwrap() // We have already validated the words.
+            .into_iter()
+            .filter(|l| !placements.iter().any(|p| p.covers_same_cells(l)))
+            .collect::<Vec<_>>();
 
-    let directions = [
-        Direction::UpRight,
-        Direction::Right,
-        Direction::DownRight,
-        Direction::Down,
-    ];
+        if duplicates.is_empty() {
+            return true;
+        }
 
-    for (row, col, direction) in
-        iproduct!(0..grid.row_count(), 0..grid.col_count(), directions.iter())
-    {
-        let cell = Cell { row, col };
-        if let Some(found) = find_word_in_direction(vec![cell], direction, grid, &all_words) {
-            let mut w = WordLocation {
-                word: found.word,
-                start_cell: found.start_cell,
-                end_cell: found.end_cell,
-                direction: found.direction,
-            };
+        for d in duplicates {
+            let filler_cells = grid
+                .word_cells(&d)
+                .unwrap() // Solvers only return locations within the grid.
+                .into_iter()
+                .filter(|c| placement_grid.value_at_cell(c) == EMPTY_CHAR)
+                .collect::<Vec<_>>();
 
-            // If the originally-provided list of words does not contain the found word,
-            // the found word must be reversed.
-            if !words.contains(&w.word.as_str()) {
-                w = w.into_reversed_location();
+            if filler_cells.is_empty() {
+                return false;
             }
 
-            word_locations.push(w);
+            for c in filler_cells {
+                grid.set_value_at_cell(&c, filler.random_char(rng));
+            }
         }
     }
 
-    word_locations.sort();
-    word_locations
+    false
 }
 
-fn opposite_direction(direction: &Direction) -> Direction {
-    match direction {
-        Direction::Up => Direction::Down,
-        Direction::UpRight => Direction::DownLeft,
-        Direction::Right => Direction::Left,
-        Direction::DownRight => Direction::UpLeft,
-        Direction::Down => Direction::Up,
-        Direction::DownLeft => Direction::UpRight,
-        Direction::Left => Direction::Right,
-        Direction::UpLeft => Direction::DownRight,
-    }
-}
-
-fn find_word_in_direction_hash(
-    mut cells: Vec<Cell>,
-    direction: &Direction,
+fn place_word_at_cell(
     grid: &Grid,
-    words: &[&str],
-    hashed: &HashSet<Vec<char>>,
-    hashed_length: usize,
-) -> Option<WordLocation> {
-    let current_cell = cells[cells.len() - 1];
-
-    if cells.len() == hashed_length
-        && !hashed.contains(
-            &cells
-                .iter()
-                .map(|c| grid.value_at_cell(c))
-                .collect::<Vec<char>>(),
-        )
-    {
+    start_cell: &Cell,
+    direction: &Direction,
+    word: &str,
+) -> Option<(Grid, WordLocation)> {
+    let length = word.chars().count();
+
+    // +1 to account for the current cell.
+    if grid.cells_remaining_in_direction(start_cell, direction) + 1 < length {
         return None;
     }
 
-    let maybe_word = cells
-        .iter()
-        .map(|c| grid.value_at_cell(c))
-        .collect::<String>();
+    let mut cell: Cell = start_cell.to_owned();
+
+    for (i, c) in word.chars().enumerate() {
+        let char_at_cell = grid.value_at_cell(&cell);
 
-    if words.contains(&maybe_word.as_str()) {
-        return Some(WordLocation {
-            word: maybe_word,
-            start_cell: cells[0],
-            end_cell: cells[cells.len() - 1],
-            direction: direction.to_owned(),
-        });
+        if char_at_cell != EMPTY_CHAR && char_at_cell != c {
+            return None;
+        }
+
+        let remaining_chars = length - i;
+        if remaining_chars > 1 {
+            cell = grid.neighbor(&cell, direction)?;
+        }
     }
 
-    let next_cell = grid.next_cell_in_direction(&current_cell, direction)?;
-    cells.push(next_cell);
-    find_word_in_direction(cells, direction, grid, words)
-}
+    let word_location = WordLocation {
+        word: word.to_owned(),
+        start_cell: *start_cell,
+        end_cell: cell,
+        direction: *direction,
+    };
 
-fn find_word_in_direction(
-    mut cells: Vec<Cell>,
-    direction: &Direction,
-    grid: &Grid,
-    words: &[&str],
-) -> Option<WordLocation> {
-    let current_cell = cells[cells.len() - 1];
+    let mut g = grid.clone();
+    g.add_word_at_location(&word_location).ok()?;
 
-    let maybe_word = cells
-        .iter()
-        .map(|c| grid.value_at_cell(c))
-        .collect::<String>();
+    Some((g, word_location))
+}
 
-    if words.contains(&maybe_word.as_str()) {
-        return Some(WordLocation {
-            word: maybe_word,
-            start_cell: cells[0],
-            end_cell: cells[cells.len() - 1],
-            direction: direction.to_owned(),
-        });
+// The least common multiple, which is how far a diagonal runs before it
+// returns to its first cell in a toroidal grid of that many rows and columns.
+fn lcm(a: usize, b: usize) -> usize {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        let r = x % y;
+        x = y;
+        y = r;
     }
+    (a * b).checked_div(x).unwrap_or(0)
+}
 
-    let next_cell = grid.next_cell_in_direction(&current_cell, direction)?;
-    cells.push(next_cell);
-    find_word_in_direction(cells, direction, grid, words)
+fn opposite_direction(direction: &Direction) -> Direction {
+    match direction {
+        Direction::Up => Direction::Down,
+        Direction::UpRight => Direction::DownLeft,
+        Direction::Right => Direction::Left,
+        Direction::DownRight => Direction::UpLeft,
+        Direction::Down => Direction::Up,
+        Direction::DownLeft => Direction::UpRight,
+        Direction::Left => Direction::Right,
+        Direction::UpLeft => Direction::DownRight,
+    }
 }
 
 #[cfg(test)]
@@ -655,7 +1235,7 @@ mod tests {
     use super::*;
 
     fn grid() -> Grid {
-        Grid::new(&vec![
+        Grid::new(&[
             vec![
                 'h', 'b', 'b', 'q', 'd', 'v', 'p', 'n', 'r', 'e', 'w', 'z', 's', 'i', 'h',
             ],
@@ -702,6 +1282,7 @@ mod tests {
                 't', 'f', 'a', 'y', 'x', 'v', 'g', 'b', 'h', 'a', 'y', 'q', 'z', 'q', 'e',
             ],
         ])
+        .unwrap()
     }
 
     fn words() -> Vec<String> {
@@ -892,41 +1473,205 @@ mod tests {
 
     #[test]
     fn test_solve_grid_reverse() {
-        assert_found_words(&solve_grid_reverse_words(
-            &grid(),
-            &words().iter().map(|w| w.as_str()).collect::<Vec<&str>>(),
-        ));
+        assert_found_words(
+            &solve_grid_reverse_words(
+                &grid(),
+                &words().iter().map(|w| w.as_str()).collect::<Vec<&str>>(),
+            )
+            .unwrap(),
+        );
     }
 
     #[test]
     fn test_solve_grid_naive() {
-        assert_found_words(&solve_grid_naive(
-            &grid(),
-            &words().iter().map(|w| w.as_str()).collect::<Vec<&str>>(),
-        ));
+        assert_found_words(
+            &solve_grid_naive(
+                &grid(),
+                &words().iter().map(|w| w.as_str()).collect::<Vec<&str>>(),
+            )
+            .unwrap(),
+        );
     }
 
     #[test]
     fn test_solve_grid_naive_hash_first_letter() {
-        assert_found_words(&solve_grid_hash_first_letter(
-            &grid(),
-            &words().iter().map(|w| w.as_str()).collect::<Vec<&str>>(),
-        ));
+        assert_found_words(
+            &solve_grid_hash_first_letter(
+                &grid(),
+                &words().iter().map(|w| w.as_str()).collect::<Vec<&str>>(),
+            )
+            .unwrap(),
+        );
     }
 
     #[test]
     fn test_solve_grid_reverse_hash_first_letter() {
-        assert_found_words(&solve_grid_reverse_hash_first_letter(
-            &grid(),
-            &words().iter().map(|w| w.as_str()).collect::<Vec<&str>>(),
-        ));
+        assert_found_words(
+            &solve_grid_reverse_hash_first_letter(
+                &grid(),
+                &words().iter().map(|w| w.as_str()).collect::<Vec<&str>>(),
+            )
+            .unwrap(),
+        );
     }
 
     #[test]
     fn test_solve_grid_reverse_hash_first_two_letters() {
-        assert_found_words(&solve_grid_reverse_hash_first_two_letters(
-            &grid(),
-            &words().iter().map(|w| w.as_str()).collect::<Vec<&str>>(),
+        assert_found_words(
+            &solve_grid_reverse_hash_first_two_letters(
+                &grid(),
+                &words().iter().map(|w| w.as_str()).collect::<Vec<&str>>(),
+            )
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_solve_grid_trie() {
+        assert_found_words(
+            &solve_grid_trie(
+                &grid(),
+                &words().iter().map(|w| w.as_str()).collect::<Vec<&str>>(),
+            )
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_solve_with_strategy() {
+        let words = words();
+        let words = words.iter().map(|w| w.as_str()).collect::<Vec<&str>>();
+
+        for strategy in SolverStrategy::iterator() {
+            assert_found_words(&solve(&grid(), &words, *strategy).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_solve_grid_all_occurrences() {
+        assert_found_words(
+            &solve_grid_all_occurrences(
+                &grid(),
+                &words().iter().map(|w| w.as_str()).collect::<Vec<&str>>(),
+            )
+            .unwrap(),
+        );
+
+        let grid = Grid::new(&[
+            vec!['i', 'n', 'i', 't'],
+            vec!['n', 'o', 'o', 'n'],
+            vec!['a', 'a', 'a', 'a'],
+        ])
+        .unwrap();
+        let words = ["in", "init", "noon", "a", "zebra"];
+
+        let found = solve_grid_all_occurrences(&grid, &words).unwrap();
+
+        let counts = count_occurrences(&words, &found);
+        assert_eq!(counts["in"], 4);
+        assert_eq!(counts["init"], 1);
+        assert_eq!(counts["noon"], 1);
+        assert_eq!(counts["a"], 4);
+        assert_eq!(counts["zebra"], 0);
+
+        assert!(found.contains(&WordLocation {
+            word: "in".to_string(),
+            start_cell: Cell { row: 0, col: 2 },
+            end_cell: Cell { row: 0, col: 1 },
+            direction: Direction::Left,
+        }));
+
+        // The naive solver only finds the first word along each ray.
+        let naive_counts = count_occurrences(&words, &solve_grid_naive(&grid, &words).unwrap());
+        assert_eq!(naive_counts["init"], 0);
+    }
+
+    #[test]
+    fn test_solve_toroidal() {
+        let grid: Grid = "atoc\nxxgx\nxxdx\n".parse().unwrap();
+        let words = ["cat", "dog"];
+        assert!(solve_grid_all_occurrences(&grid, &words)
+            .unwrap()
+            .is_empty());
+
+        let grid = grid.with_topology(Topology::Toroidal);
+        let expected = vec![
+            WordLocation {
+                word: "cat".to_string(),
+                start_cell: Cell { row: 0, col: 3 },
+                end_cell: Cell { row: 0, col: 1 },
+                direction: Direction::Right,
+            },
+            WordLocation {
+                word: "dog".to_string(),
+                start_cell: Cell { row: 2, col: 2 },
+                end_cell: Cell { row: 1, col: 2 },
+                direction: Direction::Down,
+            },
+        ];
+        for strategy in SolverStrategy::iterator() {
+            assert_eq!(solve(&grid, &words, *strategy).unwrap(), expected);
+        }
+        assert_eq!(
+            grid.answer_grid(&expected).unwrap().to_string(),
+            "a t o c\n_ _ g _\n_ _ d _\n"
+        );
+
+        // A word cannot wrap around onto its own first cell.
+        let grid = Grid::new(&[vec!['a', 'b', 'a']])
+            .unwrap()
+            .with_topology(Topology::Toroidal);
+        for strategy in SolverStrategy::iterator() {
+            assert!(solve(&grid, &["abab"], *strategy).unwrap().is_empty());
+            assert!(!solve(&grid, &["aba"], *strategy).unwrap().is_empty());
+        }
+        assert_eq!(
+            grid.cells_remaining_in_direction(&Cell { row: 0, col: 0 }, &Direction::Right),
+            2
+        );
+        assert_eq!(
+            grid.cells_remaining_in_direction(&Cell { row: 0, col: 0 }, &Direction::Down),
+            0
+        );
+
+        // Diagonals around a single row or column are not found again as duplicates.
+        for (grid, end_cell, direction) in &[
+            ("catx\n", Cell { row: 0, col: 2 }, Direction::Right),
+            ("c\na\nt\nx\n", Cell { row: 2, col: 0 }, Direction::Down),
+        ] {
+            let grid = grid
+                .parse::<Grid>()
+                .unwrap()
+                .with_topology(Topology::Toroidal);
+            let expected = vec![WordLocation {
+                word: "cat".to_string(),
+                start_cell: Cell { row: 0, col: 0 },
+                end_cell: *end_cell,
+                direction: *direction,
+            }];
+            for strategy in SolverStrategy::iterator() {
+                assert_eq!(solve(&grid, &["cat"], *strategy).unwrap(), expected);
+            }
+            assert_eq!(
+                solve_grid_all_occurrences(&grid, &["cat"]).unwrap(),
+                expected
+            );
+            assert_eq!(
+                grid.neighbor(&Cell { row: 0, col: 0 }, &Direction::DownRight),
+                None
+            );
+        }
+
+        let options = GenerationOptions {
+            topology: Topology::Toroidal,
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let puzzle = generate_grid_with_options(1, 4, &["cat"], &options, &mut rng).unwrap();
+        assert_eq!(puzzle.placements.len(), 1);
+        assert!(matches!(
+            puzzle.placements[0].direction,
+            Direction::Left | Direction::Right
         ));
     }
 
@@ -935,36 +1680,36 @@ mod tests {
         let words2 = ["if", "it", "to"];
 
         let valid_grids = vec![
-            Grid::new(&[vec!['i', 'f'], vec!['t', 'o']]),
-            Grid::new(&[vec!['i', 'f'], vec!['o', 't']]),
-            Grid::new(&[vec!['i', 't'], vec!['o', 'f']]),
-            Grid::new(&[vec!['i', 't'], vec!['f', 'o']]),
-            Grid::new(&[vec!['i', 'o'], vec!['t', 'f']]),
-            Grid::new(&[vec!['i', 'o'], vec!['f', 't']]),
-            Grid::new(&[vec!['f', 'i'], vec!['t', 'o']]),
-            Grid::new(&[vec!['f', 'i'], vec!['o', 't']]),
-            Grid::new(&[vec!['f', 'o'], vec!['t', 'i']]),
-            Grid::new(&[vec!['f', 'o'], vec!['i', 't']]),
-            Grid::new(&[vec!['f', 't'], vec!['o', 'i']]),
-            Grid::new(&[vec!['f', 't'], vec!['i', 'o']]),
-            Grid::new(&[vec!['t', 'i'], vec!['o', 'f']]),
-            Grid::new(&[vec!['t', 'i'], vec!['f', 'o']]),
-            Grid::new(&[vec!['t', 'o'], vec!['i', 'f']]),
-            Grid::new(&[vec!['t', 'o'], vec!['f', 'i']]),
-            Grid::new(&[vec!['t', 'f'], vec!['o', 'i']]),
-            Grid::new(&[vec!['t', 'f'], vec!['i', 'o']]),
-            Grid::new(&[vec!['o', 'i'], vec!['t', 'f']]),
-            Grid::new(&[vec!['o', 'i'], vec!['f', 't']]),
-            Grid::new(&[vec!['o', 't'], vec!['i', 'f']]),
-            Grid::new(&[vec!['o', 't'], vec!['f', 'i']]),
-            Grid::new(&[vec!['o', 'f'], vec!['t', 'i']]),
-            Grid::new(&[vec!['o', 'f'], vec!['i', 't']]),
+            Grid::new(&[vec!['i', 'f'], vec!['t', 'o']]).unwrap(),
+            Grid::new(&[vec!['i', 'f'], vec!['o', 't']]).unwrap(),
+            Grid::new(&[vec!['i', 't'], vec!['o', 'f']]).unwrap(),
+            Grid::new(&[vec!['i', 't'], vec!['f', 'o']]).unwrap(),
+            Grid::new(&[vec!['i', 'o'], vec!['t', 'f']]).unwrap(),
+            Grid::new(&[vec!['i', 'o'], vec!['f', 't']]).unwrap(),
+            Grid::new(&[vec!['f', 'i'], vec!['t', 'o']]).unwrap(),
+            Grid::new(&[vec!['f', 'i'], vec!['o', 't']]).unwrap(),
+            Grid::new(&[vec!['f', 'o'], vec!['t', 'i']]).unwrap(),
+            Grid::new(&[vec!['f', 'o'], vec!['i', 't']]).unwrap(),
+            Grid::new(&[vec!['f', 't'], vec!['o', 'i']]).unwrap(),
+            Grid::new(&[vec!['f', 't'], vec!['i', 'o']]).unwrap(),
+            Grid::new(&[vec!['t', 'i'], vec!['o', 'f']]).unwrap(),
+            Grid::new(&[vec!['t', 'i'], vec!['f', 'o']]).unwrap(),
+            Grid::new(&[vec!['t', 'o'], vec!['i', 'f']]).unwrap(),
+            Grid::new(&[vec!['t', 'o'], vec!['f', 'i']]).unwrap(),
+            Grid::new(&[vec!['t', 'f'], vec!['o', 'i']]).unwrap(),
+            Grid::new(&[vec!['t', 'f'], vec!['i', 'o']]).unwrap(),
+            Grid::new(&[vec!['o', 'i'], vec!['t', 'f']]).unwrap(),
+            Grid::new(&[vec!['o', 'i'], vec!['f', 't']]).unwrap(),
+            Grid::new(&[vec!['o', 't'], vec!['i', 'f']]).unwrap(),
+            Grid::new(&[vec!['o', 't'], vec!['f', 'i']]).unwrap(),
+            Grid::new(&[vec!['o', 'f'], vec!['t', 'i']]).unwrap(),
+            Grid::new(&[vec!['o', 'f'], vec!['i', 't']]).unwrap(),
         ];
 
-        let grid2 = generate_grid(2, 2, &words2).unwrap();
+        let grid2 = generate_grid(2, 2, &words2).unwrap().grid;
         assert!(valid_grids.contains(&grid2));
 
-        let found_words2 = solve_grid_naive(&grid2, &words2);
+        let found_words2 = solve_grid_naive(&grid2, &words2).unwrap();
         assert_eq!(found_words2.len(), words2.len());
 
         for (w, fw) in words2.iter().zip(found_words2.iter()) {
@@ -972,6 +1717,619 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generate_grid_from_seed() {
+        let words = words();
+        let words = words.iter().map(|w| w.as_str()).collect::<Vec<&str>>();
+
+        let grid1 = generate_grid_from_seed(15, 15, &words, 42).unwrap().grid;
+        let grid2 = generate_grid_from_seed(15, 15, &words, 42).unwrap().grid;
+        assert_eq!(grid1, grid2);
+        assert_eq!(grid1.to_string(), grid2.to_string());
+
+        let grid3 = generate_grid_from_seed(15, 15, &words, 43).unwrap().grid;
+        assert_ne!(grid1, grid3);
+
+        assert_eq!(solve_grid_naive(&grid1, &words).unwrap().len(), words.len());
+    }
+
+    #[test]
+    fn test_generated_puzzle_placements() {
+        let words = ["now", "pen", "tan", "top"];
+        let puzzle = generate_grid_from_seed(3, 3, &words, 1).unwrap();
+
+        assert_eq!(puzzle.placements.len(), words.len());
+        for (w, p) in words.iter().zip(puzzle.placements.iter()) {
+            assert_eq!(p.word, *w);
+            assert_eq!(
+                puzzle.placement_grid.one_word_grid(p),
+                puzzle.grid.one_word_grid(p)
+            );
+        }
+
+        for r in 0..puzzle.grid.row_count() {
+            for c in 0..puzzle.grid.col_count() {
+                let cell = Cell { row: r, col: c };
+                let placed = puzzle.placement_grid.value_at_cell(&cell);
+                if placed != EMPTY_CHAR {
+                    assert_eq!(puzzle.grid.value_at_cell(&cell), placed);
+                }
+            }
+        }
+
+        assert_eq!(
+            solve_grid_naive(&puzzle.grid, &words).unwrap(),
+            puzzle.placements
+        );
+        assert_eq!(
+            puzzle.grid.answer_grid(&puzzle.placements),
+            Ok(puzzle.placement_grid)
+        );
+    }
+
+    #[test]
+    fn test_generate_grid_with_options() {
+        let words = words();
+        let words = words.iter().map(|w| w.as_str()).collect::<Vec<&str>>();
+
+        for difficulty in &[Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            let options = GenerationOptions::from(*difficulty);
+            let mut rng = StdRng::seed_from_u64(3);
+            let puzzle = generate_grid_with_options(15, 15, &words, &options, &mut rng).unwrap();
+
+            assert_eq!(puzzle.placements.len(), words.len());
+            for p in &puzzle.placements {
+                assert!(difficulty.directions().contains(&p.direction));
+            }
+        }
+
+        let options = GenerationOptions::with_directions(&[Direction::Left]);
+        let mut rng = StdRng::seed_from_u64(3);
+        let puzzle = generate_grid_with_options(15, 15, &words, &options, &mut rng).unwrap();
+        assert!(puzzle
+            .placements
+            .iter()
+            .all(|p| p.direction == Direction::Left));
+
+        let options = GenerationOptions::with_directions(&[]);
+        assert_eq!(
+            generate_grid_with_options(15, 15, &words, &options, &mut rng),
+            Err(WordsearchError::NoDirections)
+        );
+    }
+
+    #[test]
+    fn test_generate_grid_fill() {
+        let words = ["año", "niño", "señal"];
+
+        for fill in &[
+            FillStrategy::Alphabet(vec!['x']),
+            FillStrategy::Language(Language::Spanish),
+            FillStrategy::WordLetters,
+        ] {
+            let options = GenerationOptions {
+                fill: fill.clone(),
+                ..Default::default()
+            };
+            let mut rng = StdRng::seed_from_u64(0);
+            let puzzle = generate_grid_with_options(6, 6, &words, &options, &mut rng).unwrap();
+
+            let allowed = match fill {
+                FillStrategy::Alphabet(chars) => chars.clone(),
+                FillStrategy::Language(l) => {
+                    l.letter_frequencies().iter().map(|(c, _)| *c).collect()
+                }
+                FillStrategy::WordLetters => words.iter().flat_map(|w| w.chars()).collect(),
+            };
+
+            for (row, placed) in puzzle.grid.chars.iter().zip(&puzzle.placement_grid.chars) {
+                for (c, p) in row.iter().zip(placed) {
+                    assert!(*p != EMPTY_CHAR || allowed.contains(c));
+                }
+            }
+            assert_eq!(
+                solve_grid_all_occurrences(&puzzle.grid, &words).unwrap(),
+                puzzle.placements
+            );
+        }
+
+        let options = GenerationOptions {
+            fill: FillStrategy::Alphabet(vec![]),
+            ..Default::default()
+        };
+        assert_eq!(
+            generate_grid_with_options(6, 6, &words, &options, &mut StdRng::seed_from_u64(0)),
+            Err(WordsearchError::EmptyAlphabet)
+        );
+    }
+
+    #[test]
+    fn test_generate_grid_pinned() {
+        let cat: WordLocation = "cat 0,0 0,2 right".parse().unwrap();
+        let dog: WordLocation = "dog 2,4 0,4 up".parse().unwrap();
+        let options = GenerationOptions {
+            pinned: vec![cat.clone(), dog.clone()],
+            ..GenerationOptions::with_directions(&[Direction::Down])
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let puzzle =
+            generate_grid_with_options(5, 5, &["cat", "emu", "owl"], &options, &mut rng).unwrap();
+
+        // Pinned words are in the puzzle whether or not they are in the word list.
+        let words = ["cat", "dog", "emu", "owl"];
+        assert_eq!(puzzle.placements.len(), words.len());
+        assert!(puzzle.placements.contains(&cat));
+        assert!(puzzle.placements.contains(&dog));
+        assert_eq!(puzzle.placement_grid.chars[0][..3], ['c', 'a', 't']);
+        assert_eq!(
+            solve_grid_all_occurrences(&puzzle.grid, &words).unwrap(),
+            puzzle.placements
+        );
+
+        // There is nothing left to place when every word is pinned.
+        let puzzle = generate_grid_with_options(5, 5, &[], &options, &mut rng).unwrap();
+        assert_eq!(puzzle.placements, vec![cat, dog]);
+
+        let pinned = |pins: &[&str]| GenerationOptions {
+            pinned: pins.iter().map(|p| p.parse().unwrap()).collect(),
+            ..Default::default()
+        };
+        assert_eq!(
+            generate_grid_with_options(
+                5,
+                5,
+                &[],
+                &pinned(&["cat 0,0 0,2 right", "dog 0,2 2,2 down"]),
+                &mut rng
+            ),
+            Err(WordsearchError::PinConflict {
+                word: "dog".to_string(),
+                cell: Cell { row: 0, col: 2 },
+                letter: 'd',
+                existing: 't',
+            })
+        );
+        for pin in &["cat 0,3 0,5 right", "cat 0,0 0,3 right"] {
+            assert_eq!(
+                generate_grid_with_options(5, 5, &[], &pinned(&[pin]), &mut rng),
+                Err(WordsearchError::LocationOutOfBounds {
+                    word: "cat".to_string()
+                })
+            );
+        }
+
+        let options = GenerationOptions {
+            mask: Some(Shape::Circle.mask(5, 5)),
+            ..pinned(&["cat 0,0 0,2 right"])
+        };
+        assert_eq!(
+            generate_grid_with_options(5, 5, &[], &options, &mut rng),
+            Err(WordsearchError::PinConflict {
+                word: "cat".to_string(),
+                cell: Cell { row: 0, col: 0 },
+                letter: 'c',
+                existing: MASKED_CHAR,
+            })
+        );
+    }
+
+    #[test]
+    fn test_generate_grid_template() {
+        let template: Grid = "c__\n__x\n".parse().unwrap();
+        let options = GenerationOptions {
+            template: Some(template.clone()),
+            ..GenerationOptions::with_directions(&[Direction::Right])
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // The only place for the word is across the template's letter.
+        let puzzle = generate_grid_with_options(2, 3, &["cat"], &options, &mut rng).unwrap();
+        assert_eq!(puzzle.placement_grid.to_string(), "c a t\n_ _ x\n");
+        assert_eq!(puzzle.grid.value_at_cell(&Cell { row: 1, col: 2 }), 'x');
+        assert_eq!(
+            puzzle.placements,
+            vec!["cat 0,0 0,2 right".parse().unwrap()]
+        );
+
+        assert_eq!(
+            generate_grid_with_options(2, 3, &["dog"], &options, &mut rng),
+            Err(WordsearchError::Unplaceable)
+        );
+        assert_eq!(
+            generate_grid_with_options(3, 3, &["cat"], &options, &mut rng),
+            Err(WordsearchError::TemplateSizeMismatch {
+                rows: 3,
+                cols: 3,
+                template_rows: 2,
+                template_cols: 3,
+            })
+        );
+        assert_eq!(
+            generate_grid_with_options(
+                2,
+                3,
+                &[],
+                &GenerationOptions {
+                    pinned: vec!["dog 1,2 1,0 left".parse().unwrap()],
+                    ..options
+                },
+                &mut rng
+            ),
+            Err(WordsearchError::PinConflict {
+                word: "dog".to_string(),
+                cell: Cell { row: 1, col: 2 },
+                letter: 'd',
+                existing: 'x',
+            })
+        );
+    }
+
+    #[test]
+    fn test_max_word_length() {
+        let options = GenerationOptions::with_directions(&[Direction::Down]);
+        assert_eq!(options.max_word_length(4, 9), 4);
+
+        let options = GenerationOptions::with_directions(&[Direction::Left, Direction::UpRight]);
+        assert_eq!(options.max_word_length(4, 9), 9);
+        assert_eq!(options.max_word_length(9, 4), 4);
+
+        assert_eq!(
+            GenerationOptions::with_directions(&[]).max_word_length(4, 9),
+            0
+        );
+
+        // With a mask, only lines of open cells count.
+        let mut options = GenerationOptions::with_directions(&[Direction::Right]);
+        options.mask = Some(Shape::Letter('L').mask(7, 5));
+        assert_eq!(options.max_word_length(7, 5), 5);
+        options.mask = Some(Shape::Letter('T').mask(7, 5));
+        options.directions = vec![Direction::Down, Direction::DownRight];
+        assert_eq!(options.max_word_length(7, 5), 7);
+        options.mask = Some(Shape::Letter('O').mask(7, 5));
+        assert_eq!(options.max_word_length(7, 5), 5);
+    }
+
+    #[test]
+    fn test_generate_grid_toroidal() {
+        let words = ["apple", "grape", "lemon", "mango", "peach"];
+        let options = GenerationOptions {
+            topology: Topology::Toroidal,
+            ..Default::default()
+        };
+        let mut wrapped = 0;
+
+        for seed in 0..5 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let puzzle = generate_grid_with_options(5, 5, &words, &options, &mut rng).unwrap();
+
+            assert_eq!(puzzle.grid.topology(), Topology::Toroidal);
+            assert_eq!(
+                solve_grid_all_occurrences(&puzzle.grid, &words).unwrap(),
+                puzzle.placements
+            );
+
+            let bounded = puzzle.grid.clone().with_topology(Topology::Bounded);
+            wrapped += puzzle
+                .placements
+                .iter()
+                .filter(|p| bounded.word_cells(p).is_none())
+                .count();
+        }
+        assert!(wrapped > 0);
+
+        // Words may be as long as the grid, but no longer.
+        assert_eq!(options.max_word_length(4, 6), 12);
+        let options = GenerationOptions {
+            directions: vec![Direction::Right],
+            ..options
+        };
+        assert_eq!(
+            generate_grid_with_options(3, 3, &["abcd"], &options, &mut StdRng::seed_from_u64(0)),
+            Err(WordsearchError::WordTooLong {
+                word: "abcd".to_string(),
+                max: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_generate_grid_mask() {
+        let words = ["heart", "kiss", "love", "rose"];
+        let mask = Shape::Heart.mask(9, 9);
+        let options = GenerationOptions {
+            mask: Some(mask.clone()),
+            ..Default::default()
+        };
+
+        for seed in 0..5 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let puzzle = generate_grid_with_options(9, 9, &words, &options, &mut rng).unwrap();
+
+            for (r, c) in iproduct!(0..9, 0..9) {
+                let cell = Cell { row: r, col: c };
+                let letter = puzzle.grid.value_at_cell(&cell);
+                assert_eq!(mask.is_open(&cell), letter != MASKED_CHAR);
+                assert_ne!(letter, EMPTY_CHAR);
+            }
+            assert_eq!(
+                solve_grid_all_occurrences(&puzzle.grid, &words).unwrap(),
+                puzzle.placements
+            );
+            assert_eq!(
+                puzzle.grid.to_string().parse::<Grid>().unwrap(),
+                puzzle.grid
+            );
+        }
+
+        // Words are not read through masked out cells.
+        let grid: Grid = "ab#cd\n#####\n".parse().unwrap();
+        assert!(solve_grid_all_occurrences(&grid, &["abcd"])
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            grid.answer_grid(&solve_grid_all_occurrences(&grid, &["ab"]).unwrap())
+                .unwrap()
+                .to_string(),
+            "a b # _ _\n# # # # #\n"
+        );
+
+        assert_eq!(
+            generate_grid_with_options(8, 9, &words, &options, &mut StdRng::seed_from_u64(0)),
+            Err(WordsearchError::MaskSizeMismatch {
+                rows: 8,
+                cols: 9,
+                mask_rows: 9,
+                mask_cols: 9
+            })
+        );
+        assert_eq!(
+            generate_grid_with_options(9, 9, &["a#b"], &options, &mut StdRng::seed_from_u64(0)),
+            Err(WordsearchError::InvalidCharacter {
+                word: "a#b".to_string(),
+                character: '#'
+            })
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Grid::new(&[vec!['a', 'b'], vec!['c']]),
+            Err(WordsearchError::RaggedGrid {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+
+        assert_eq!(
+            generate_grid(2, 2, &[]),
+            Err(WordsearchError::EmptyWordList)
+        );
+        assert_eq!(
+            generate_grid(2, 2, &["abc"]),
+            Err(WordsearchError::WordTooLong {
+                word: "abc".to_string(),
+                max: 2
+            })
+        );
+        assert_eq!(
+            generate_grid(2, 2, &["a b"]),
+            Err(WordsearchError::InvalidCharacter {
+                word: "a b".to_string(),
+                character: ' '
+            })
+        );
+        assert_eq!(
+            generate_grid(2, 2, &["ab", "cd", "ef"]),
+            Err(WordsearchError::Unplaceable)
+        );
+
+        let grid = Grid::empty(2, 2);
+        assert_eq!(
+            solve_grid_reverse_hash_first_two_letters(&grid, &["ab", "c"]),
+            Err(WordsearchError::WordTooShort {
+                word: "c".to_string(),
+                min: 2
+            })
+        );
+        assert_eq!(
+            solve_grid_naive(&grid, &[""]),
+            Err(WordsearchError::WordTooShort {
+                word: "".to_string(),
+                min: 1
+            })
+        );
+
+        let wl = WordLocation {
+            word: "abc".to_string(),
+            start_cell: Cell { row: 0, col: 0 },
+            end_cell: Cell { row: 0, col: 2 },
+            direction: Direction::Right,
+        };
+        assert_eq!(
+            grid.one_word_grid(&wl),
+            Err(WordsearchError::LocationOutOfBounds {
+                word: "abc".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_generate_grid_unique() {
+        let words = ["at", "on", "it", "so"];
+
+        let mut duplicates = 0;
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let options = GenerationOptions::default();
+            let puzzle = generate_grid_with_options(6, 6, &words, &options, &mut rng).unwrap();
+            let found = solve_grid_all_occurrences(&puzzle.grid, &words).unwrap();
+            assert!(count_occurrences(&words, &found).values().all(|c| *c == 1));
+
+            let mut rng = StdRng::seed_from_u64(seed);
+            let options = GenerationOptions {
+                ensure_unique: false,
+                ..Default::default()
+            };
+            let puzzle = generate_grid_with_options(6, 6, &words, &options, &mut rng).unwrap();
+            let found = solve_grid_all_occurrences(&puzzle.grid, &words).unwrap();
+            if count_occurrences(&words, &found).values().any(|c| *c > 1) {
+                duplicates += 1;
+            }
+        }
+        assert!(duplicates > 0);
+
+        assert_eq!(
+            generate_grid(8, 8, &["in", "initiate"]),
+            Err(WordsearchError::WordWithinWord {
+                word: "in".to_string(),
+                containing: "initiate".to_string()
+            })
+        );
+        assert_eq!(
+            generate_grid(6, 6, &["top", "pot"]),
+            Err(WordsearchError::WordWithinWord {
+                word: "top".to_string(),
+                containing: "pot".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_direction_from_str() {
+        for direction in Direction::iterator() {
+            assert_eq!(direction.to_string().parse(), Ok(*direction));
+        }
+        assert_eq!("Down-Left".parse(), Ok(Direction::DownLeft));
+        assert!("sideways".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn test_topology_from_str() {
+        for topology in Topology::iterator() {
+            assert_eq!(topology.to_string().parse(), Ok(*topology));
+        }
+        assert_eq!("Toroidal".parse(), Ok(Topology::Toroidal));
+        assert_eq!(
+            "flat".parse::<Topology>(),
+            Err(WordsearchError::UnknownTopology("flat".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_grid_from_str() {
+        let g = grid();
+        assert_eq!(g.to_string().parse(), Ok(g));
+
+        let expected = Grid::new(&[vec!['a', 'b', 'c'], vec!['d', 'e', 'f']]).unwrap();
+        assert_eq!("abc\ndef\n".parse(), Ok(expected.clone()));
+        assert_eq!("\n  a b c  \n\n\td e f\r\n\n".parse(), Ok(expected));
+
+        assert_eq!(
+            "a b c\n\nd e\n".parse::<Grid>(),
+            Err(WordsearchError::RaggedLine {
+                line: 3,
+                column: 4,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            "abc\nabcd\n".parse::<Grid>(),
+            Err(WordsearchError::RaggedLine {
+                line: 2,
+                column: 4,
+                expected: 3,
+                found: 4
+            })
+        );
+        assert_eq!(" \n\n".parse::<Grid>(), Err(WordsearchError::EmptyGrid));
+    }
+
+    #[test]
+    fn test_grid_navigation() {
+        let cell = |row, col| Cell { row, col };
+        let grid = Grid::empty(2, 3);
+
+        assert_eq!(
+            grid.neighbor(&cell(0, 0), &Direction::Right),
+            Some(cell(0, 1))
+        );
+        assert_eq!(grid.neighbor(&cell(0, 2), &Direction::Right), None);
+        assert_eq!(grid.neighbor(&cell(5, 0), &Direction::Right), None);
+        assert_eq!(
+            grid.ray(&cell(1, 0), &Direction::Right).collect::<Vec<_>>(),
+            vec![cell(1, 0), cell(1, 1), cell(1, 2)]
+        );
+        assert_eq!(grid.ray(&cell(2, 0), &Direction::Right).count(), 0);
+        assert_eq!(
+            grid.cells_remaining_in_direction(&cell(0, 3), &Direction::Left),
+            0
+        );
+
+        let grid = grid.with_topology(Topology::Toroidal);
+        assert_eq!(
+            grid.neighbor(&cell(0, 2), &Direction::Right),
+            Some(cell(0, 0))
+        );
+        assert_eq!(
+            grid.neighbor(&cell(0, 0), &Direction::UpLeft),
+            Some(cell(1, 2))
+        );
+        assert_eq!(grid.neighbor(&cell(5, 0), &Direction::Right), None);
+        // A diagonal visits every cell before it comes back round to the start.
+        assert_eq!(
+            grid.ray(&cell(0, 0), &Direction::DownRight)
+                .collect::<Vec<_>>(),
+            vec![
+                cell(0, 0),
+                cell(1, 1),
+                cell(0, 2),
+                cell(1, 0),
+                cell(0, 1),
+                cell(1, 2)
+            ]
+        );
+        assert_eq!(
+            Grid::empty(1, 3)
+                .with_topology(Topology::Toroidal)
+                .neighbor(&cell(0, 0), &Direction::Down),
+            None
+        );
+    }
+
+    #[test]
+    fn test_word_location_cells() {
+        let grid: Grid = "atc\nxxx\n".parse().unwrap();
+        let location = WordLocation {
+            word: "cat".to_string(),
+            start_cell: Cell { row: 0, col: 2 },
+            end_cell: Cell { row: 0, col: 1 },
+            direction: Direction::Right,
+        };
+        assert_eq!(location.cells(&grid).count(), 0);
+
+        let grid = grid.with_topology(Topology::Toroidal);
+        assert_eq!(
+            location.cells(&grid).collect::<Vec<_>>(),
+            vec![
+                Cell { row: 0, col: 2 },
+                Cell { row: 0, col: 0 },
+                Cell { row: 0, col: 1 }
+            ]
+        );
+
+        let reversed = location.into_reversed_location();
+        assert_eq!(
+            reversed
+                .cells(&grid)
+                .map(|c| grid.value_at_cell(&c))
+                .collect::<String>(),
+            "tac"
+        );
+    }
+
     #[test]
     fn test_grid_cells_remaining_in_direction() {
         let g1 = Grid::empty(1, 1);
@@ -1,73 +1,790 @@
-use clap::{App, Arg};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, IsTerminal, Read};
+use std::process;
+use std::str::FromStr;
 use wordsearch::*;
 
+const GENERATE_COMMAND: &str = "generate";
+const SOLVE_COMMAND: &str = "solve";
+const VERIFY_COMMAND: &str = "verify";
+const RENDER_COMMAND: &str = "render";
+
 const ROWS_SIZE_ARG: &str = "rows";
 const COLS_SIZE_ARG: &str = "cols";
+const SEED_ARG: &str = "seed";
+const DIFFICULTY_ARG: &str = "difficulty";
+const DIRECTIONS_ARG: &str = "directions";
+const ALLOW_DUPLICATES_ARG: &str = "allow-duplicates";
+const GRID_ARG: &str = "grid";
+const SOLVER_ARG: &str = "solver";
+const WORDS_ARG: &str = "words";
+const WORD_ARG: &str = "word";
+const OUTPUT_ARG: &str = "output";
+const FORMAT_ARG: &str = "format";
+const ALPHABET_ARG: &str = "alphabet";
+const LANGUAGE_ARG: &str = "language";
+const FILL_FROM_WORDS_ARG: &str = "fill-from-words";
+const FOLD_DIACRITICS_ARG: &str = "fold-diacritics";
+const EXACT_ARG: &str = "exact";
+const CELL_SIZE_ARG: &str = "cell-size";
+const FONT_SIZE_ARG: &str = "font-size";
+const FONT_FAMILY_ARG: &str = "font-family";
+const NO_WORD_BANK_ARG: &str = "no-word-bank";
+const TITLE_ARG: &str = "title";
+const AUTHOR_ARG: &str = "author";
+const PUZZLE_ARG: &str = "puzzle";
+const SHAPE_ARG: &str = "shape";
+const STENCIL_ARG: &str = "stencil";
+const COLOR_ARG: &str = "color";
+const WRAP_ARG: &str = "wrap";
+const SNAKE_ARG: &str = "snake";
+const PIN_ARG: &str = "pin";
+const TEMPLATE_ARG: &str = "template";
+
+const TEXT_FORMAT: &str = "text";
+const ANSWERS_FORMAT: &str = "answers";
+const SVG_FORMAT: &str = "svg";
+const SVG_ANSWERS_FORMAT: &str = "svg-answers";
+const HTML_FORMAT: &str = "html";
+const TERMINAL_FORMAT: &str = "terminal";
+const PUZZLE_FORMAT: &str = "puzzle";
+
+// Exit code for input that could not be read or used. Clap also exits with 1 for invalid arguments.
+const EXIT_ERROR: i32 = 1;
+// Exit code for a grid that does not contain each word exactly once.
+const EXIT_FAILED: i32 = 2;
 
 fn main() {
     let matches = App::new("Wordsearch")
         .version("0.1.0")
         .author("Rob Dimsdale-Zucker")
         .about("Generates and solves wordsearches")
-        .arg(
-            Arg::with_name(ROWS_SIZE_ARG)
-                .long(ROWS_SIZE_ARG)
-                .help("Number of rows in generated wordsearch.")
-                .takes_value(true)
-                .value_name("ROWS"),
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name(GENERATE_COMMAND)
+                .about("Generates a wordsearch and writes the grid or puzzle to a file")
+                .arg(
+                    Arg::with_name(ROWS_SIZE_ARG)
+                        .long(ROWS_SIZE_ARG)
+                        .help("Number of rows in generated wordsearch. Defaults to 15, or the height of the stencil.")
+                        .takes_value(true)
+                        .value_name("ROWS"),
+                )
+                .arg(
+                    Arg::with_name(COLS_SIZE_ARG)
+                        .long(COLS_SIZE_ARG)
+                        .help("Number of columns in generated wordsearch. Defaults to 15, or the width of the stencil.")
+                        .takes_value(true)
+                        .value_name("COLS"),
+                )
+                .arg(
+                    Arg::with_name(SEED_ARG)
+                        .long(SEED_ARG)
+                        .help("Seed for generating a reproducible wordsearch. Defaults to a random seed.")
+                        .takes_value(true)
+                        .value_name("SEED"),
+                )
+                .arg(
+                    Arg::with_name(DIFFICULTY_ARG)
+                        .long(DIFFICULTY_ARG)
+                        .help("Preset for the directions words may be placed in. Defaults to hard.")
+                        .takes_value(true)
+                        .possible_values(&["easy", "medium", "hard"])
+                        .value_name("DIFFICULTY"),
+                )
+                .arg(
+                    Arg::with_name(DIRECTIONS_ARG)
+                        .long(DIRECTIONS_ARG)
+                        .help("Comma-separated directions words may be placed in, e.g. right,down,down-right.")
+                        .takes_value(true)
+                        .use_delimiter(true)
+                        .conflicts_with(DIFFICULTY_ARG)
+                        .value_name("DIRECTIONS"),
+                )
+                .arg(
+                    Arg::with_name(ALLOW_DUPLICATES_ARG)
+                        .long(ALLOW_DUPLICATES_ARG)
+                        .help("Skips checking that each word occurs exactly once. Faster, but puzzles may be ambiguous."),
+                )
+                .arg(
+                    Arg::with_name(ALPHABET_ARG)
+                        .long(ALPHABET_ARG)
+                        .help("Letters to fill the rest of the grid with, e.g. abcdefghijklmnñopqrstuvwxyz. Defaults to a to z.")
+                        .takes_value(true)
+                        .conflicts_with_all(&[LANGUAGE_ARG, FILL_FROM_WORDS_ARG])
+                        .value_name("LETTERS"),
+                )
+                .arg(
+                    Arg::with_name(LANGUAGE_ARG)
+                        .long(LANGUAGE_ARG)
+                        .help("Fills the rest of the grid with letters as common as they are in the language.")
+                        .takes_value(true)
+                        .possible_values(&["english", "french", "german", "spanish"])
+                        .conflicts_with(FILL_FROM_WORDS_ARG)
+                        .value_name("LANGUAGE"),
+                )
+                .arg(
+                    Arg::with_name(FILL_FROM_WORDS_ARG)
+                        .long(FILL_FROM_WORDS_ARG)
+                        .help("Fills the rest of the grid with letters as common as they are in the words."),
+                )
+                .arg(
+                    Arg::with_name(SHAPE_ARG)
+                        .long(SHAPE_ARG)
+                        .help("Shape of the grid: circle, heart, or a letter from A to Z. Cells outside the shape are left blank.")
+                        .takes_value(true)
+                        .conflicts_with(STENCIL_ARG)
+                        .value_name("SHAPE"),
+                )
+                .arg(
+                    Arg::with_name(STENCIL_ARG)
+                        .long(STENCIL_ARG)
+                        .help("File with the shape of the grid drawn in ASCII art. Spaces and dots are left blank and any other character is a cell. Use - to read from stdin.")
+                        .takes_value(true)
+                        .value_name("FILE"),
+                )
+                .arg(
+                    Arg::with_name(PIN_ARG)
+                        .long(PIN_ARG)
+                        .help("A word to place at a fixed location before any others, as 'word row,col row,col direction' like the answer key of a puzzle file. May be repeated.")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .value_name("LOCATION"),
+                )
+                .arg(
+                    Arg::with_name(TEMPLATE_ARG)
+                        .long(TEMPLATE_ARG)
+                        .help("File with a grid to start from. Its letters are kept and words may cross them where they match; _ cells are free. Use - to read from stdin.")
+                        .takes_value(true)
+                        .value_name("FILE"),
+                )
+                .arg(
+                    Arg::with_name(WRAP_ARG)
+                        .long(WRAP_ARG)
+                        .help("Lets words wrap around the edges of the grid, continuing from the opposite edge."),
+                )
+                .arg(
+                    Arg::with_name(SNAKE_ARG)
+                        .long(SNAKE_ARG)
+                        .help("Places each word along a path that may bend at every letter, as in Boggle. The directions limit each step of the path. Solve with solve --snake."),
+                )
+                .arg(
+                    Arg::with_name(FORMAT_ARG)
+                        .long(FORMAT_ARG)
+                        .help("Output format. A puzzle file has the grid, the words, the answer key and the settings. Defaults to text.")
+                        .takes_value(true)
+                        .possible_values(&[TEXT_FORMAT, PUZZLE_FORMAT])
+                        .value_name("FORMAT"),
+                )
+                .args(&puzzle_metadata_args())
+                .args(&word_list_args())
+                .arg(output_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name(SOLVE_COMMAND)
+                .about("Finds the words in a grid and prints their locations")
+                .args(&grid_args())
+                .arg(
+                    Arg::with_name(SOLVER_ARG)
+                        .long(SOLVER_ARG)
+                        .help("Solver strategy to use. Defaults to trie.")
+                        .takes_value(true)
+                        .value_name("STRATEGY"),
+                )
+                .arg(snake_arg().conflicts_with(SOLVER_ARG))
+                .arg(color_arg())
+                .args(&word_list_args())
+                .args(&normalization_args()),
         )
-        .arg(
-            Arg::with_name(COLS_SIZE_ARG)
-                .long(COLS_SIZE_ARG)
-                .help("Number of columns in generated wordsearch.")
-                .takes_value(true)
-                .value_name("COLS"),
+        .subcommand(
+            SubCommand::with_name(VERIFY_COMMAND)
+                .about("Checks that each word occurs exactly once in a grid")
+                .args(&grid_args())
+                .arg(snake_arg())
+                .args(&word_list_args())
+                .args(&normalization_args()),
+        )
+        .subcommand(
+            SubCommand::with_name(RENDER_COMMAND)
+                .about("Converts a grid to another format")
+                .args(&grid_args())
+                .arg(
+                    Arg::with_name(FORMAT_ARG)
+                        .long(FORMAT_ARG)
+                        .help("Output format. The answers and terminal formats highlight the words, html is a page for playing the puzzle in a browser, and puzzle is a file with the grid, the words and the answer key. These need the words. Defaults to text.")
+                        .takes_value(true)
+                        .possible_values(&[TEXT_FORMAT, ANSWERS_FORMAT, SVG_FORMAT, SVG_ANSWERS_FORMAT, HTML_FORMAT, TERMINAL_FORMAT, PUZZLE_FORMAT])
+                        .value_name("FORMAT"),
+                )
+                .arg(
+                    Arg::with_name(CELL_SIZE_ARG)
+                        .long(CELL_SIZE_ARG)
+                        .help("Width and height of each cell in pixels, for SVG. Defaults to 40.")
+                        .takes_value(true)
+                        .value_name("PIXELS"),
+                )
+                .arg(
+                    Arg::with_name(FONT_SIZE_ARG)
+                        .long(FONT_SIZE_ARG)
+                        .help("Height of the letters in pixels, for SVG. Defaults to 24.")
+                        .takes_value(true)
+                        .value_name("PIXELS"),
+                )
+                .arg(
+                    Arg::with_name(FONT_FAMILY_ARG)
+                        .long(FONT_FAMILY_ARG)
+                        .help("CSS font family of the letters, for SVG. Defaults to sans-serif.")
+                        .takes_value(true)
+                        .value_name("FONT"),
+                )
+                .arg(
+                    Arg::with_name(NO_WORD_BANK_ARG)
+                        .long(NO_WORD_BANK_ARG)
+                        .help("Leaves out the list of words below the grid, for SVG."),
+                )
+                .args(&puzzle_metadata_args())
+                .arg(color_arg())
+                .args(
+                    &word_list_args()
+                        .into_iter()
+                        .map(|a| a.required(false))
+                        .collect::<Vec<_>>(),
+                )
+                .args(&normalization_args())
+                .arg(output_arg()),
         )
         .get_matches();
 
-    let words = [
-        "anxious",
-        "blossom",
-        "border",
-        "coordinated",
-        "follow",
-        "guess",
-        "hope",
-        "impede",
-        "initiate",
-        "instrument",
-        "mind",
-        "nose",
-        "plausible",
-        "prescribe",
-        "produce",
-        "recite",
-        "robin",
-        "stress",
-        "vivacious",
-    ];
-
-    let rows = match matches.value_of(ROWS_SIZE_ARG) {
-        None => 15,
-        Some(v) => v.parse().unwrap(),
+    let code = match matches.subcommand() {
+        (GENERATE_COMMAND, Some(m)) => generate(m),
+        (SOLVE_COMMAND, Some(m)) => solve_grid_file(m),
+        (VERIFY_COMMAND, Some(m)) => verify(m),
+        (RENDER_COMMAND, Some(m)) => render(m),
+        _ => unreachable!(), // A subcommand is required.
+    };
+
+    process::exit(code);
+}
+
+fn grid_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name(GRID_ARG)
+            .long(GRID_ARG)
+            .help("File containing the grid, one row per line. Letters may be separated by spaces. Use - to read from stdin.")
+            .takes_value(true)
+            .required_unless(PUZZLE_ARG)
+            .conflicts_with(PUZZLE_ARG)
+            .value_name("FILE"),
+        Arg::with_name(PUZZLE_ARG)
+            .long(PUZZLE_ARG)
+            .help("Puzzle file to read the grid and the words from, instead of --grid. The words may be overridden with --words and --word. Use - to read from stdin.")
+            .takes_value(true)
+            .value_name("FILE"),
+        Arg::with_name(WRAP_ARG)
+            .long(WRAP_ARG)
+            .help("Reads words that wrap around the edges of the grid. Puzzle files say whether their grid wraps.")
+            .conflicts_with(PUZZLE_ARG),
+    ]
+}
+
+fn puzzle_metadata_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name(TITLE_ARG)
+            .long(TITLE_ARG)
+            .help("Title of the puzzle, for puzzle files and HTML. Defaults to the puzzle file's title, or Wordsearch for HTML.")
+            .takes_value(true)
+            .value_name("TITLE"),
+        Arg::with_name(AUTHOR_ARG)
+            .long(AUTHOR_ARG)
+            .help("Author of the puzzle, for puzzle files.")
+            .takes_value(true)
+            .value_name("AUTHOR"),
+    ]
+}
+
+fn output_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(OUTPUT_ARG)
+        .long(OUTPUT_ARG)
+        .help("File to write to. Defaults to stdout.")
+        .takes_value(true)
+        .value_name("FILE")
+}
+
+fn snake_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(SNAKE_ARG)
+        .long(SNAKE_ARG)
+        .help("Finds words along paths that may bend at every letter, as in Boggle, without using a letter twice.")
+}
+
+fn color_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name(COLOR_ARG)
+        .long(COLOR_ARG)
+        .help("When to highlight words in colour. Auto uses colour when writing to a terminal and NO_COLOR is not set. Defaults to auto.")
+        .takes_value(true)
+        .possible_values(&["auto", "always", "never"])
+        .value_name("WHEN")
+}
+
+// Whether to write ANSI colours, following https://no-color.org for auto.
+fn use_colour(matches: &ArgMatches, to_stdout: bool) -> bool {
+    match matches.value_of(COLOR_ARG).unwrap_or("auto") {
+        "always" => true,
+        "never" => false,
+        _ => {
+            to_stdout
+                && io::stdout().is_terminal()
+                && env::var_os("NO_COLOR").map_or(true, |v| v.is_empty())
+        }
+    }
+}
+
+fn word_list_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name(WORDS_ARG)
+            .long(WORDS_ARG)
+            .help("File containing the words, one per line. Use - to read from stdin. A # starts a comment that runs to the end of the line.")
+            .takes_value(true)
+            .required_unless_one(&[WORD_ARG, PUZZLE_ARG])
+            .value_name("FILE"),
+        Arg::with_name(WORD_ARG)
+            .long(WORD_ARG)
+            .help("A word to include. May be repeated.")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .value_name("WORD"),
+    ]
+}
+
+fn normalization_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name(FOLD_DIACRITICS_ARG)
+            .long(FOLD_DIACRITICS_ARG)
+            .help("Matches accented letters with their unaccented forms, e.g. é with e."),
+        Arg::with_name(EXACT_ARG)
+            .long(EXACT_ARG)
+            .help("Matches letters exactly. By default case is ignored, as are spaces and punctuation in words.")
+            .conflicts_with(FOLD_DIACRITICS_ARG),
+    ]
+}
+
+fn read_normalization(matches: &ArgMatches) -> Normalization {
+    if matches.is_present(EXACT_ARG) {
+        Normalization::exact()
+    } else {
+        Normalization {
+            fold_diacritics: matches.is_present(FOLD_DIACRITICS_ARG),
+            ..Default::default()
+        }
+    }
+}
+
+fn exit_with_error(e: impl Display) -> ! {
+    eprintln!("error: {}", e);
+    process::exit(EXIT_ERROR);
+}
+
+fn parse_arg<T>(matches: &ArgMatches, name: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    matches.value_of(name).map(|v| {
+        v.parse().unwrap_or_else(|e| {
+            exit_with_error(format!("invalid value '{}' for --{}: {}", v, name, e))
+        })
+    })
+}
+
+// Reads a whole file, or stdin if the path is `-`.
+fn read_input(path: &str) -> String {
+    if path == "-" {
+        let mut text = String::new();
+        io::stdin()
+            .read_to_string(&mut text)
+            .unwrap_or_else(|e| exit_with_error(format!("unable to read stdin: {}", e)));
+        text
+    } else {
+        fs::read_to_string(path)
+            .unwrap_or_else(|e| exit_with_error(format!("unable to read {}: {}", path, e)))
+    }
+}
+
+fn write_output(matches: &ArgMatches, text: &str) {
+    match matches.value_of(OUTPUT_ARG) {
+        None => print!("{}", text),
+        Some(path) => fs::write(path, text)
+            .unwrap_or_else(|e| exit_with_error(format!("unable to write {}: {}", path, e))),
+    }
+}
+
+// Reads the puzzle file, checking its answer key, if one was given.
+fn read_puzzle(matches: &ArgMatches) -> Option<Puzzle> {
+    matches.value_of(PUZZLE_ARG).map(|path| {
+        if path == "-" && matches.value_of(WORDS_ARG) == Some("-") {
+            exit_with_error("the puzzle and the words cannot both be read from stdin");
+        }
+
+        read_input(path)
+            .parse()
+            .unwrap_or_else(|e| exit_with_error(format!("{}: {}", path, e)))
+    })
+}
+
+fn read_grid(matches: &ArgMatches, puzzle: Option<&Puzzle>) -> Grid {
+    if let Some(p) = puzzle {
+        return p.grid.clone();
+    }
+
+    let path = matches.value_of(GRID_ARG).unwrap(); // The grid argument is required without a puzzle.
+
+    if path == "-" && matches.value_of(WORDS_ARG) == Some("-") {
+        exit_with_error("the grid and the words cannot both be read from stdin");
+    }
+
+    let grid = read_input(path)
+        .parse::<Grid>()
+        .unwrap_or_else(|e| exit_with_error(format!("{}: {}", path, e)));
+
+    if matches.is_present(WRAP_ARG) {
+        grid.with_topology(Topology::Toroidal)
+    } else {
+        grid
+    }
+}
+
+// Reads the words from the word list file and any words given inline, or
+// from the puzzle if there are none.
+fn read_words(matches: &ArgMatches, puzzle: Option<&Puzzle>) -> Vec<String> {
+    let mut words = match matches.value_of(WORDS_ARG) {
+        None => vec![],
+        Some(path) => parse_word_list(&read_input(path)),
     };
 
-    let cols = match matches.value_of(COLS_SIZE_ARG) {
-        None => 15,
-        Some(v) => v.parse().unwrap(),
+    if let Some(vs) = matches.values_of(WORD_ARG) {
+        words.extend(vs.map(|v| v.to_string()));
+    }
+
+    // Words in a puzzle are kept as they are spelled there.
+    let words = match puzzle {
+        Some(p) if words.is_empty() => p.words.clone(),
+        _ => normalize_words(&words),
     };
 
-    let grid = generate_grid(rows, cols, &words).unwrap();
+    if words.is_empty() {
+        exit_with_error(WordsearchError::EmptyWordList);
+    }
+
+    words
+}
+
+fn generate(matches: &ArgMatches) -> i32 {
+    let stencil = matches.value_of(STENCIL_ARG).map(|path| {
+        if path == "-" && matches.value_of(WORDS_ARG) == Some("-") {
+            exit_with_error("the stencil and the words cannot both be read from stdin");
+        }
 
-    println!("words: {:?}", words);
-    print!("{}", grid);
+        read_input(path)
+            .parse::<Mask>()
+            .unwrap_or_else(|e| exit_with_error(format!("{}: {}", path, e)))
+    });
 
-    let found_words = solve_grid_hash_first_letter(&grid, &words);
+    let template = matches.value_of(TEMPLATE_ARG).map(|path| {
+        if path == "-"
+            && (matches.value_of(WORDS_ARG) == Some("-")
+                || matches.value_of(STENCIL_ARG) == Some("-"))
+        {
+            exit_with_error("the template and the words or stencil cannot both be read from stdin");
+        }
 
-    for w in &found_words {
-        println!("{:?}", w);
-        println!("{}", grid.one_word_grid(w));
+        read_input(path)
+            .parse::<Grid>()
+            .unwrap_or_else(|e| exit_with_error(format!("{}: {}", path, e)))
+    });
+
+    let rows = parse_arg(matches, ROWS_SIZE_ARG)
+        .or_else(|| stencil.as_ref().map(|m| m.row_count()))
+        .or_else(|| template.as_ref().map(|t| t.row_count()))
+        .unwrap_or(15);
+    let cols = parse_arg(matches, COLS_SIZE_ARG)
+        .or_else(|| stencil.as_ref().map(|m| m.col_count()))
+        .or_else(|| template.as_ref().map(|t| t.col_count()))
+        .unwrap_or(15);
+    let seed = parse_arg(matches, SEED_ARG).unwrap_or_else(rand::random);
+
+    let mut options = match matches.values_of(DIRECTIONS_ARG) {
+        Some(vs) => GenerationOptions::with_directions(
+            &vs.map(|v| v.parse().unwrap_or_else(|e| exit_with_error(e)))
+                .collect::<Vec<Direction>>(),
+        ),
+        None => match parse_arg::<Difficulty>(matches, DIFFICULTY_ARG) {
+            None => GenerationOptions::default(),
+            Some(d) => GenerationOptions::from(d),
+        },
+    };
+
+    options.ensure_unique = !matches.is_present(ALLOW_DUPLICATES_ARG);
+
+    if let Some(v) = matches.value_of(ALPHABET_ARG) {
+        options.fill = FillStrategy::Alphabet(v.chars().collect());
+    } else if let Some(language) = parse_arg(matches, LANGUAGE_ARG) {
+        options.fill = FillStrategy::Language(language);
+    } else if matches.is_present(FILL_FROM_WORDS_ARG) {
+        options.fill = FillStrategy::WordLetters;
+    }
+
+    if matches.is_present(WRAP_ARG) {
+        options.topology = Topology::Toroidal;
+    }
+
+    options.mask =
+        stencil.or_else(|| parse_arg::<Shape>(matches, SHAPE_ARG).map(|s| s.mask(rows, cols)));
+    options.template = template;
+
+    if let Some(vs) = matches.values_of(PIN_ARG) {
+        options.pinned = vs
+            .map(|v| v.parse().unwrap_or_else(|e| exit_with_error(e)))
+            .collect::<Vec<WordLocation>>();
+    }
+
+    let words = read_words(matches, None);
+    let words = words.iter().map(|w| w.as_str()).collect::<Vec<_>>();
+
+    if matches.is_present(SNAKE_ARG) {
+        if matches.value_of(FORMAT_ARG) == Some(PUZZLE_FORMAT) {
+            exit_with_error("the puzzle format cannot hold words that bend");
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let puzzle = generate_snake_grid_with_options(rows, cols, &words, &options, &mut rng)
+            .unwrap_or_else(|e| exit_with_error(e));
+
+        eprintln!("seed: {}", seed);
+        write_output(matches, &puzzle.grid.to_string());
+        return 0;
+    }
+
+    // Report every word that is too long up front, rather than only the first.
+    let max = options.max_word_length(rows, cols);
+    let too_long = words
+        .iter()
+        .filter(|w| w.chars().count() > max && !options.pinned.iter().any(|p| p.word == **w))
+        .collect::<Vec<_>>();
+
+    if !too_long.is_empty() {
+        for w in too_long {
+            eprintln!(
+                "error: word '{}' has {} letters but a {}x{} grid only fits {} in the allowed directions",
+                w,
+                w.chars().count(),
+                rows,
+                cols,
+                max
+            );
+        }
+        return EXIT_ERROR;
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let puzzle = generate_grid_with_options(rows, cols, &words, &options, &mut rng)
+        .unwrap_or_else(|e| exit_with_error(e));
+
+    // The seed goes to stderr so that stdout is only the grid.
+    eprintln!("seed: {}", seed);
+
+    if matches.value_of(FORMAT_ARG) == Some(PUZZLE_FORMAT) {
+        // Pinned words are part of the puzzle even if they are not in the word list.
+        let mut all_words = words.clone();
+        for p in &options.pinned {
+            if !all_words.contains(&p.word.as_str()) {
+                all_words.push(&p.word);
+            }
+        }
+
+        let mut puzzle =
+            Puzzle::new(puzzle.grid, &all_words).unwrap_or_else(|e| exit_with_error(e));
+        puzzle.metadata = PuzzleMetadata {
+            title: matches.value_of(TITLE_ARG).map(|t| t.to_string()),
+            author: matches.value_of(AUTHOR_ARG).map(|a| a.to_string()),
+            seed: Some(seed),
+            directions: Some(options.directions),
+        };
+        write_output(matches, &puzzle.to_string());
+    } else {
+        write_output(matches, &puzzle.grid.to_string());
+    }
+
+    0
+}
+
+fn solve_grid_file(matches: &ArgMatches) -> i32 {
+    let puzzle = read_puzzle(matches);
+    let grid = read_grid(matches, puzzle.as_ref());
+    let strategy = parse_arg(matches, SOLVER_ARG).unwrap_or(SolverStrategy::Trie);
+
+    let words = read_words(matches, puzzle.as_ref());
+    let words = words.iter().map(|w| w.as_str()).collect::<Vec<_>>();
+
+    let colour = use_colour(matches, true);
+    let found_words = if matches.is_present(SNAKE_ARG) {
+        let found = solve_snake_normalized(&grid, &words, &read_normalization(matches))
+            .unwrap_or_else(|e| exit_with_error(e));
+
+        println!("{}", render_terminal_paths(&grid, &found, colour));
+        for p in &found {
+            println!("{:?}", p);
+        }
+        found.into_iter().map(|p| p.word).collect::<Vec<_>>()
+    } else {
+        let found = solve_normalized(&grid, &words, strategy, &read_normalization(matches))
+            .unwrap_or_else(|e| exit_with_error(e));
+
+        println!("{}", render_terminal(&grid, &found, colour));
+        for w in &found {
+            println!("{:?}", w);
+        }
+        found.into_iter().map(|l| l.word).collect::<Vec<_>>()
+    };
+
+    let mut code = 0;
+    for word in words
+        .iter()
+        .filter(|w| !found_words.iter().any(|f| f == *w))
+    {
+        println!("not found: {}", word);
+        code = EXIT_FAILED;
     }
 
-    assert_eq!(found_words.len(), words.len());
+    code
+}
+
+fn verify(matches: &ArgMatches) -> i32 {
+    let puzzle = read_puzzle(matches);
+    let grid = read_grid(matches, puzzle.as_ref());
+
+    let words = read_words(matches, puzzle.as_ref());
+    let words = words.iter().map(|w| w.as_str()).collect::<Vec<_>>();
+
+    let counts = if matches.is_present(SNAKE_ARG) {
+        let found = solve_snake_normalized(&grid, &words, &read_normalization(matches))
+            .unwrap_or_else(|e| exit_with_error(e));
+        words
+            .iter()
+            .map(|w| (w.to_string(), found.iter().filter(|p| p.word == *w).count()))
+            .collect::<BTreeMap<_, _>>()
+    } else {
+        let found = solve_normalized(
+            &grid,
+            &words,
+            SolverStrategy::AllOccurrences,
+            &read_normalization(matches),
+        )
+        .unwrap_or_else(|e| exit_with_error(e));
+        count_occurrences(&words, &found)
+    };
+
+    let mut code = 0;
+    for (word, count) in counts {
+        if count != 1 {
+            println!("{}: found {} times", word, count);
+            code = EXIT_FAILED;
+        }
+    }
+
+    if code == 0 {
+        println!("ok: each of the {} words occurs exactly once", words.len());
+    }
+
+    code
+}
+
+fn render(matches: &ArgMatches) -> i32 {
+    let puzzle = read_puzzle(matches);
+    let grid = read_grid(matches, puzzle.as_ref());
+    let format = matches.value_of(FORMAT_ARG).unwrap_or(TEXT_FORMAT);
+
+    // Words are only needed for the answers and the word bank.
+    let words = if format == ANSWERS_FORMAT
+        || format == SVG_ANSWERS_FORMAT
+        || format == HTML_FORMAT
+        || format == TERMINAL_FORMAT
+        || format == PUZZLE_FORMAT
+        || puzzle.is_some()
+        || matches.is_present(WORDS_ARG)
+        || matches.is_present(WORD_ARG)
+    {
+        read_words(matches, puzzle.as_ref())
+    } else {
+        vec![]
+    };
+    let words = words.iter().map(|w| w.as_str()).collect::<Vec<_>>();
+
+    let answers = || {
+        solve_normalized(
+            &grid,
+            &words,
+            SolverStrategy::AllOccurrences,
+            &read_normalization(matches),
+        )
+        .unwrap_or_else(|e| exit_with_error(e))
+    };
+
+    let mut svg_options = SvgOptions::default();
+    if let Some(size) = parse_arg(matches, CELL_SIZE_ARG) {
+        svg_options.cell_size = size;
+    }
+    if let Some(size) = parse_arg(matches, FONT_SIZE_ARG) {
+        svg_options.font_size = size;
+    }
+    if let Some(family) = matches.value_of(FONT_FAMILY_ARG) {
+        svg_options.font_family = family.to_string();
+    }
+    svg_options.word_bank = !matches.is_present(NO_WORD_BANK_ARG);
+
+    let mut metadata = puzzle.map(|p| p.metadata).unwrap_or_default();
+    if let Some(title) = matches.value_of(TITLE_ARG) {
+        metadata.title = Some(title.to_string());
+    }
+    if let Some(author) = matches.value_of(AUTHOR_ARG) {
+        metadata.author = Some(author.to_string());
+    }
+
+    let rendered = match format {
+        ANSWERS_FORMAT => grid
+            .answer_grid(&answers())
+            .unwrap_or_else(|e| exit_with_error(e))
+            .to_string(),
+        SVG_FORMAT => render_svg(&grid, &words, &svg_options),
+        SVG_ANSWERS_FORMAT => render_svg_answer_key(&grid, &words, &answers(), &svg_options),
+        TERMINAL_FORMAT => render_terminal(
+            &grid,
+            &answers(),
+            use_colour(matches, !matches.is_present(OUTPUT_ARG)),
+        ),
+        HTML_FORMAT => render_html(
+            &grid,
+            &words,
+            &answers(),
+            metadata.title.as_deref().unwrap_or("Wordsearch"),
+        ),
+        PUZZLE_FORMAT => {
+            // The answer key is solved as it is checked when loading, whatever the normalization flags.
+            let mut puzzle =
+                Puzzle::new(grid.clone(), &words).unwrap_or_else(|e| exit_with_error(e));
+            puzzle.metadata = metadata;
+            puzzle.to_string()
+        }
+        _ => grid.to_string(),
+    };
+
+    write_output(matches, &rendered);
+
+    0
 }
@@ -0,0 +1,294 @@
+use crate::{Cell, WordsearchError};
+use std::fmt;
+use std::slice::Iter;
+use std::str::FromStr;
+
+/// Which cells of a grid may hold letters. Cells that are masked out are never
+/// used for a word or filled in, so the grid takes the shape of the open cells.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mask {
+    open: Vec<Vec<bool>>,
+}
+
+impl Mask {
+    /// Returns an error if the rows are not all the same length, or no cell is open.
+    pub fn new(open: &[Vec<bool>]) -> Result<Mask, WordsearchError> {
+        if let Some(first) = open.first() {
+            if let Some((row, r)) = open
+                .iter()
+                .enumerate()
+                .find(|(_, r)| r.len() != first.len())
+            {
+                return Err(WordsearchError::RaggedGrid {
+                    row,
+                    expected: first.len(),
+                    found: r.len(),
+                });
+            }
+        }
+
+        if !open.iter().flatten().any(|o| *o) {
+            return Err(WordsearchError::EmptyMask);
+        }
+
+        Ok(Mask {
+            open: open.to_vec(),
+        })
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.open.len()
+    }
+
+    pub fn col_count(&self) -> usize {
+        if self.open.is_empty() {
+            0
+        } else {
+            self.open[0].len()
+        }
+    }
+
+    /// Whether the cell may hold a letter. Cells outside the mask are not open.
+    pub fn is_open(&self, cell: &Cell) -> bool {
+        self.open
+            .get(cell.row)
+            .and_then(|r| r.get(cell.col))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    // Opens the cells whose centres, scaled to run from -1 to 1 across the
+    // grid with y increasing upwards, are inside the shape.
+    fn from_fn(rows: usize, cols: usize, inside: impl Fn(f64, f64) -> bool) -> Mask {
+        Mask {
+            open: (0..rows)
+                .map(|r| {
+                    (0..cols)
+                        .map(|c| {
+                            let x = 2.0 * (c as f64 + 0.5) / cols as f64 - 1.0;
+                            let y = 1.0 - 2.0 * (r as f64 + 0.5) / rows as f64;
+                            inside(x, y)
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Parses an ASCII-art stencil, one row per line.
+///
+/// Spaces and `.` are masked out and any other character is an open cell.
+/// Lines shorter than the longest are masked out to its length, and blank
+/// lines before and after the stencil are ignored.
+impl FromStr for Mask {
+    type Err = WordsearchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s.lines().map(|l| l.trim_end()).collect::<Vec<_>>();
+        let first = lines.iter().position(|l| !l.is_empty()).unwrap_or(0);
+        let last = lines
+            .iter()
+            .rposition(|l| !l.is_empty())
+            .map_or(0, |i| i + 1);
+        let lines = &lines[first..last.max(first)];
+
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let open = lines
+            .iter()
+            .map(|l| {
+                let mut row = l.chars().map(|c| c != ' ' && c != '.').collect::<Vec<_>>();
+                row.resize(width, false);
+                row
+            })
+            .collect::<Vec<_>>();
+
+        Mask::new(&open)
+    }
+}
+
+/// Built-in shapes for masking a grid.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Shape {
+    /// A circle, or an ellipse in a grid that is not square.
+    Circle,
+    Heart,
+    /// A capital letter from A to Z.
+    Letter(char),
+}
+
+impl Shape {
+    /// The shapes other than letters.
+    pub fn iterator() -> Iter<'static, Shape> {
+        static SHAPES: [Shape; 2] = [Shape::Circle, Shape::Heart];
+        SHAPES.iter()
+    }
+
+    /// The shape stretched to fill a grid of the given size.
+    pub fn mask(&self, rows: usize, cols: usize) -> Mask {
+        match self {
+            Shape::Circle => Mask::from_fn(rows, cols, |x, y| x * x + y * y <= 1.0),
+            Shape::Heart => Mask::from_fn(rows, cols, |x, y| {
+                // The heart curve (x² + y² - 1)³ = x²y³, which spans roughly
+                // -1.15 to 1.15 across and -1 to 1.25 up.
+                let (x, y) = (x * 1.15, y * 1.15 + 0.1);
+                (x * x + y * y - 1.0).powi(3) - x * x * y.powi(3) <= 0.0
+            }),
+            Shape::Letter(letter) => {
+                // Letters are validated when parsed, so the glyph is always found.
+                // Each cell takes the pixel of the glyph under its centre.
+                let glyph = glyph(*letter).unwrap_or(&GLYPHS[0].1);
+                Mask {
+                    open: (0..rows)
+                        .map(|r| {
+                            let line = glyph[(2 * r + 1) * GLYPH_HEIGHT / (2 * rows)].as_bytes();
+                            (0..cols)
+                                .map(|c| line[(2 * c + 1) * GLYPH_WIDTH / (2 * cols)] == b'#')
+                                .collect()
+                        })
+                        .collect(),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Shape::Circle => write!(f, "circle"),
+            Shape::Heart => write!(f, "heart"),
+            Shape::Letter(letter) => write!(f, "{}", letter),
+        }
+    }
+}
+
+impl FromStr for Shape {
+    type Err = WordsearchError;
+
+    /// Parses `circle`, `heart` or a single letter from A to Z, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut chars = s.chars();
+
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            let letter = c.to_ascii_uppercase();
+            if glyph(letter).is_some() {
+                return Ok(Shape::Letter(letter));
+            }
+        }
+
+        let s = s.to_lowercase();
+        Shape::iterator()
+            .find(|shape| shape.to_string() == s)
+            .copied()
+            .ok_or(WordsearchError::UnknownShape(s))
+    }
+}
+
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 7;
+
+// A 5x7 bitmap font of the capital letters.
+#[rustfmt::skip]
+static GLYPHS: [(char, [&str; GLYPH_HEIGHT]); 26] = [
+    ('A', [".###.", "#...#", "#...#", "#####", "#...#", "#...#", "#...#"]),
+    ('B', ["####.", "#...#", "#...#", "####.", "#...#", "#...#", "####."]),
+    ('C', [".###.", "#...#", "#....", "#....", "#....", "#...#", ".###."]),
+    ('D', ["####.", "#...#", "#...#", "#...#", "#...#", "#...#", "####."]),
+    ('E', ["#####", "#....", "#....", "####.", "#....", "#....", "#####"]),
+    ('F', ["#####", "#....", "#....", "####.", "#....", "#....", "#...."]),
+    ('G', [".###.", "#...#", "#....", "#.###", "#...#", "#...#", ".###."]),
+    ('H', ["#...#", "#...#", "#...#", "#####", "#...#", "#...#", "#...#"]),
+    ('I', [".###.", "..#..", "..#..", "..#..", "..#..", "..#..", ".###."]),
+    ('J', ["..###", "...#.", "...#.", "...#.", "...#.", "#..#.", ".##.."]),
+    ('K', ["#...#", "#..#.", "#.#..", "##...", "#.#..", "#..#.", "#...#"]),
+    ('L', ["#....", "#....", "#....", "#....", "#....", "#....", "#####"]),
+    ('M', ["#...#", "##.##", "#.#.#", "#.#.#", "#...#", "#...#", "#...#"]),
+    ('N', ["#...#", "#...#", "##..#", "#.#.#", "#..##", "#...#", "#...#"]),
+    ('O', [".###.", "#...#", "#...#", "#...#", "#...#", "#...#", ".###."]),
+    ('P', ["####.", "#...#", "#...#", "####.", "#....", "#....", "#...."]),
+    ('Q', [".###.", "#...#", "#...#", "#...#", "#.#.#", "#..#.", ".##.#"]),
+    ('R', ["####.", "#...#", "#...#", "####.", "#.#..", "#..#.", "#...#"]),
+    ('S', [".####", "#....", "#....", ".###.", "....#", "....#", "####."]),
+    ('T', ["#####", "..#..", "..#..", "..#..", "..#..", "..#..", "..#.."]),
+    ('U', ["#...#", "#...#", "#...#", "#...#", "#...#", "#...#", ".###."]),
+    ('V', ["#...#", "#...#", "#...#", "#...#", "#...#", ".#.#.", "..#.."]),
+    ('W', ["#...#", "#...#", "#...#", "#.#.#", "#.#.#", "#.#.#", ".#.#."]),
+    ('X', ["#...#", "#...#", ".#.#.", "..#..", ".#.#.", "#...#", "#...#"]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#..", "..#.."]),
+    ('Z', ["#####", "....#", "...#.", "..#..", ".#...", "#....", "#####"]),
+];
+
+fn glyph(letter: char) -> Option<&'static [&'static str; GLYPH_HEIGHT]> {
+    GLYPHS
+        .iter()
+        .find(|(c, _)| *c == letter)
+        .map(|(_, glyph)| glyph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Draws the mask in the stencil format.
+    fn draw(mask: &Mask) -> String {
+        mask.open
+            .iter()
+            .map(|r| {
+                r.iter()
+                    .map(|o| if *o { '#' } else { '.' })
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_shapes() {
+        assert_eq!(
+            draw(&Shape::Circle.mask(5, 7)),
+            ".#####.\n#######\n#######\n#######\n.#####.\n"
+        );
+        assert_eq!(
+            draw(&Shape::Heart.mask(9, 9)),
+            ".###.###.\n#########\n#########\n#########\n#########\n.#######.\n..#####..\n...###...\n....#....\n"
+        );
+        assert_eq!(
+            draw(&Shape::Letter('T').mask(7, 5)),
+            "#####\n..#..\n..#..\n..#..\n..#..\n..#..\n..#..\n"
+        );
+        // Letters are stretched to fill the grid.
+        assert_eq!(
+            draw(&Shape::Letter('L').mask(4, 4)),
+            "#...\n#...\n#...\n####\n"
+        );
+    }
+
+    #[test]
+    fn test_shape_from_str() {
+        assert_eq!("Heart".parse(), Ok(Shape::Heart));
+        assert_eq!(" circle ".parse(), Ok(Shape::Circle));
+        assert_eq!("q".parse(), Ok(Shape::Letter('Q')));
+        assert_eq!(
+            "é".parse::<Shape>(),
+            Err(WordsearchError::UnknownShape("é".to_string()))
+        );
+        assert_eq!(
+            "star".parse::<Shape>(),
+            Err(WordsearchError::UnknownShape("star".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_mask_from_str() {
+        let mask: Mask = "\n  ##\n\n#x.#\n #\n\n".parse().unwrap();
+        assert_eq!(draw(&mask), "..##\n....\n##.#\n.#..\n");
+        assert!(mask.is_open(&Cell { row: 2, col: 1 }));
+        assert!(!mask.is_open(&Cell { row: 2, col: 2 }));
+        assert!(!mask.is_open(&Cell { row: 9, col: 0 }));
+
+        assert_eq!("".parse::<Mask>(), Err(WordsearchError::EmptyMask));
+        assert_eq!(" . \n..".parse::<Mask>(), Err(WordsearchError::EmptyMask));
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{solve, Grid, SolverStrategy, WordLocation, WordsearchError};
+use std::collections::BTreeMap;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// How letters in the grid and the words are compared when solving.
+///
+/// Words are always composed into NFC first, so that each letter fits in one cell.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Normalization {
+    /// Whether upper and lower case letters match.
+    pub fold_case: bool,
+    /// Whether spaces, hyphens and anything else that is not a letter or digit
+    /// is removed from the words, e.g. "ice cream" is found as "icecream".
+    pub strip_non_letters: bool,
+    /// Whether accented letters match their unaccented forms, e.g. "é" matches "e".
+    pub fold_diacritics: bool,
+}
+
+impl Normalization {
+    /// Letters only match if they are identical.
+    pub fn exact() -> Normalization {
+        Normalization {
+            fold_case: false,
+            strip_non_letters: false,
+            fold_diacritics: false,
+        }
+    }
+
+    pub fn normalize_char(&self, c: char) -> char {
+        let mut c = c;
+
+        if self.fold_diacritics {
+            // Only letters that decompose into a base letter and combining marks are folded,
+            // so that e.g. Hangul syllables are not split into their jamo.
+            let decomposed = c.to_string().nfd().collect::<Vec<char>>();
+            if decomposed[1..].iter().all(|m| is_combining_mark(*m)) {
+                c = decomposed[0];
+            }
+        }
+
+        if self.fold_case {
+            c = c.to_lowercase().next().unwrap_or(c);
+        }
+
+        c
+    }
+
+    pub fn normalize_word(&self, word: &str) -> String {
+        word.nfc()
+            .filter(|c| !self.strip_non_letters || c.is_alphanumeric())
+            .map(|c| self.normalize_char(c))
+            .collect()
+    }
+
+    pub fn normalize_grid(&self, grid: &Grid) -> Grid {
+        Grid {
+            chars: grid
+                .chars
+                .iter()
+                .map(|row| row.iter().map(|c| self.normalize_char(*c)).collect())
+                .collect(),
+            topology: grid.topology,
+        }
+    }
+}
+
+impl Default for Normalization {
+    /// Folds case and strips non-letters, but keeps diacritics.
+    fn default() -> Self {
+        Normalization {
+            fold_case: true,
+            strip_non_letters: true,
+            fold_diacritics: false,
+        }
+    }
+}
+
+/// Solves the grid after normalizing both it and the words.
+///
+/// Each location reports the word as it was spelled in `words`. If several words
+/// normalize to the same letters, each of them is reported at every location.
+pub fn solve_normalized(
+    grid: &Grid,
+    words: &[&str],
+    strategy: SolverStrategy,
+    normalization: &Normalization,
+) -> Result<Vec<WordLocation>, WordsearchError> {
+    let spellings = spellings_by_normalized_word(words, normalization)?;
+
+    let normalized_words = spellings.keys().map(|w| w.as_str()).collect::<Vec<_>>();
+    let found = solve(
+        &normalization.normalize_grid(grid),
+        &normalized_words,
+        strategy,
+    )?;
+
+    let mut located = Vec::new();
+    for l in found {
+        for spelling in &spellings[&l.word] {
+            located.push(WordLocation {
+                word: spelling.to_string(),
+                ..l.clone()
+            });
+        }
+    }
+
+    located.sort();
+    Ok(located)
+}
+
+// The words grouped by how they normalize. Words that normalize to nothing
+// cannot be found.
+pub(crate) fn spellings_by_normalized_word<'a>(
+    words: &[&'a str],
+    normalization: &Normalization,
+) -> Result<BTreeMap<String, Vec<&'a str>>, WordsearchError> {
+    let mut spellings: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for w in words {
+        let normalized = normalization.normalize_word(w);
+        if normalized.is_empty() {
+            return Err(WordsearchError::WordTooShort {
+                word: w.to_string(),
+                min: 1,
+            });
+        }
+        spellings.entry(normalized).or_default().push(w);
+    }
+    Ok(spellings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cell;
+
+    fn found_words(found: &[WordLocation]) -> Vec<&str> {
+        found.iter().map(|l| l.word.as_str()).collect()
+    }
+
+    #[test]
+    fn test_solve_normalized() {
+        let grid = "ICECREAM\nTSHIRTXX\nCAFEXXXX\n".parse::<Grid>().unwrap();
+        let words = ["Ice cream", "T-shirt", "café"];
+
+        let found = solve_normalized(
+            &grid,
+            &words,
+            SolverStrategy::Trie,
+            &Normalization::default(),
+        )
+        .unwrap();
+        assert_eq!(found_words(&found), vec!["Ice cream", "T-shirt"]);
+        assert_eq!(found[0].start_cell, Cell { row: 0, col: 0 });
+        assert_eq!(found[0].end_cell, Cell { row: 0, col: 7 });
+
+        let normalization = Normalization {
+            fold_diacritics: true,
+            ..Default::default()
+        };
+        let found = solve_normalized(&grid, &words, SolverStrategy::Trie, &normalization).unwrap();
+        assert_eq!(found_words(&found), vec!["Ice cream", "T-shirt", "café"]);
+
+        let found =
+            solve_normalized(&grid, &words, SolverStrategy::Trie, &Normalization::exact()).unwrap();
+        assert!(found.is_empty());
+
+        // Every spelling is reported at the location.
+        let found = solve_normalized(
+            &grid,
+            &["tshirt", "T-Shirt"],
+            SolverStrategy::Naive,
+            &Normalization::default(),
+        )
+        .unwrap();
+        assert_eq!(found_words(&found), vec!["T-Shirt", "tshirt"]);
+
+        assert_eq!(
+            solve_normalized(
+                &grid,
+                &["--"],
+                SolverStrategy::Trie,
+                &Normalization::default()
+            ),
+            Err(WordsearchError::WordTooShort {
+                word: "--".to_string(),
+                min: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_normalize_word() {
+        let normalization = Normalization {
+            fold_diacritics: true,
+            ..Default::default()
+        };
+
+        assert_eq!(normalization.normalize_word("Crème Brûlée"), "cremebrulee");
+        // Decomposed input is composed before folding.
+        assert_eq!(
+            Normalization::default().normalize_word("Cafe\u{301}"),
+            "café"
+        );
+        assert_eq!(normalization.normalize_word("Ἀθῆναι"), "αθηναι");
+        assert_eq!(normalization.normalize_word("한국어"), "한국어");
+        assert_eq!(Normalization::exact().normalize_word("T-Shirt"), "T-Shirt");
+    }
+}
@@ -0,0 +1,713 @@
+use crate::{
+    solve_normalized, Cell, Direction, Grid, Normalization, SolverStrategy, Topology, WordLocation,
+    WordsearchError,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// The version of the plain-text puzzle format written by `Puzzle`'s `Display`.
+pub const PUZZLE_FORMAT_VERSION: u32 = 1;
+
+/// A wordsearch as it is stored or shared: the grid, the words to find, and
+/// where they are.
+///
+/// A puzzle is saved with `Display` and loaded with `FromStr` in a plain-text
+/// format with a header of `key: value` lines followed by `[grid]`, `[words]`
+/// and `[answers]` sections:
+///
+/// ```text
+/// version: 1
+/// title: Animals
+/// author: Rob
+/// seed: 42
+/// size: 3x3
+/// directions: right, down-left
+///
+/// [grid]
+/// c a t
+/// x o x
+/// g o x
+///
+/// [words]
+/// cat
+/// tog
+///
+/// [answers]
+/// cat 0,0 0,2 right
+/// tog 0,2 2,0 down-left
+/// ```
+///
+/// Only `version` is required in the header. Line breaks and backslashes in
+/// the title and author are written as `\n`, `\r` and `\\`. A grid whose words wrap around
+/// its edges has a `topology: toroidal` line after the size. Each answer is the word followed
+/// by its start and end cells, as `row,col` counting from zero, and its direction.
+/// Loading checks that the answer key is exactly what the solver finds in the
+/// grid.
+///
+/// With the `serde` feature, a puzzle is serialized as:
+///
+/// ```json
+/// {
+///   "grid": ["cat", "xox", "gox"],
+///   "words": ["cat", "tog"],
+///   "answers": [
+///     {
+///       "word": "cat",
+///       "start_cell": { "row": 0, "col": 0 },
+///       "end_cell": { "row": 0, "col": 2 },
+///       "direction": "right"
+///     }
+///   ],
+///   "metadata": {
+///     "title": "Animals",
+///     "author": "Rob",
+///     "seed": 42,
+///     "directions": ["right", "down-left"]
+///   }
+/// }
+/// ```
+///
+/// Each row of the grid is a string with one char per cell, and a grid whose
+/// words wrap around its edges is written as
+/// `{ "rows": ["cat", "xox", "gox"], "topology": "toroidal" }`. Rows and columns
+/// count from zero, and directions are named as in `Direction`'s `Display`.
+/// `metadata` and each of its fields may be left out.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Puzzle {
+    pub grid: Grid,
+    pub words: Vec<String>,
+    /// The answer key, one location for every occurrence of each word.
+    pub answers: Vec<WordLocation>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub metadata: PuzzleMetadata,
+}
+
+/// Optional details about where a puzzle came from.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct PuzzleMetadata {
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub title: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub author: Option<String>,
+    /// The seed the grid was generated from, if it was generated from one.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub seed: Option<u64>,
+    /// The directions words were allowed to be placed in.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub directions: Option<Vec<Direction>>,
+}
+
+impl Puzzle {
+    /// A puzzle with every location the solver finds the words at as its answer key.
+    pub fn new(grid: Grid, words: &[&str]) -> Result<Puzzle, WordsearchError> {
+        let answers = solve_normalized(
+            &grid,
+            words,
+            SolverStrategy::AllOccurrences,
+            &Normalization::default(),
+        )?;
+
+        Ok(Puzzle {
+            grid,
+            words: words.iter().map(|w| w.to_string()).collect(),
+            answers,
+            metadata: PuzzleMetadata::default(),
+        })
+    }
+
+    /// Checks that each answer spells out its word from its start to its end
+    /// cell, and that the answer key has every location the solver finds the
+    /// words at. Case, spaces and punctuation in the words are ignored, as with
+    /// `Normalization::default()`.
+    pub fn validate(&self) -> Result<(), WordsearchError> {
+        let normalization = Normalization::default();
+        let words = self.words.iter().map(|w| w.as_str()).collect::<Vec<_>>();
+
+        for a in &self.answers {
+            let spelled = self.grid.word_cells(a).map(|cells| {
+                cells
+                    .iter()
+                    .map(|c| normalization.normalize_char(self.grid.value_at_cell(c)))
+                    .collect::<String>()
+            });
+
+            if !words.contains(&a.word.as_str())
+                || spelled != Some(normalization.normalize_word(&a.word))
+            {
+                return Err(WordsearchError::IncorrectAnswer {
+                    word: a.word.clone(),
+                    start_cell: a.start_cell,
+                    end_cell: a.end_cell,
+                });
+            }
+        }
+
+        let found = solve_normalized(
+            &self.grid,
+            &words,
+            SolverStrategy::AllOccurrences,
+            &normalization,
+        )?;
+
+        if let Some(f) = found
+            .iter()
+            .find(|f| !self.answers.iter().any(|a| a.covers_same_cells(f)))
+        {
+            return Err(WordsearchError::MissingAnswer {
+                word: f.word.clone(),
+                start_cell: f.start_cell,
+                end_cell: f.end_cell,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "version: {}", PUZZLE_FORMAT_VERSION)?;
+        if let Some(title) = &self.metadata.title {
+            writeln!(f, "title: {}", escape_header_value(title))?;
+        }
+        if let Some(author) = &self.metadata.author {
+            writeln!(f, "author: {}", escape_header_value(author))?;
+        }
+        if let Some(seed) = self.metadata.seed {
+            writeln!(f, "seed: {}", seed)?;
+        }
+        writeln!(
+            f,
+            "size: {}x{}",
+            self.grid.row_count(),
+            self.grid.col_count()
+        )?;
+        if self.grid.topology() != Topology::Bounded {
+            writeln!(f, "topology: {}", self.grid.topology())?;
+        }
+        if let Some(directions) = &self.metadata.directions {
+            let names = directions.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+            writeln!(f, "directions: {}", names.join(", "))?;
+        }
+
+        writeln!(f, "\n[grid]\n{}", self.grid)?;
+
+        writeln!(f, "[words]")?;
+        for w in &self.words {
+            writeln!(f, "{}", w)?;
+        }
+
+        writeln!(f, "\n[answers]")?;
+        for a in &self.answers {
+            writeln!(
+                f,
+                "{} {} {} {}",
+                a.word, a.start_cell, a.end_cell, a.direction
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Section {
+    Header,
+    Grid,
+    Words,
+    Answers,
+}
+
+/// Parses and validates a puzzle in the format written by `Display`.
+///
+/// Line numbers in errors count from one.
+impl FromStr for Puzzle {
+    type Err = WordsearchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |line: usize, message: String| WordsearchError::InvalidPuzzleLine {
+            line: line + 1,
+            message,
+        };
+
+        let mut section = Section::Header;
+        let mut seen = vec![];
+        let mut version = None;
+        let mut size = None;
+        let mut topology = Topology::Bounded;
+        let mut metadata = PuzzleMetadata::default();
+        // Lines outside the grid are left blank, so that errors from parsing
+        // the grid have the right line numbers.
+        let mut grid_text = String::new();
+        let mut words = vec![];
+        let mut answers = vec![];
+
+        for (i, line) in s.lines().enumerate() {
+            let trimmed = line.trim();
+            let is_section = trimmed.starts_with('[') && trimmed.ends_with(']');
+
+            if section == Section::Grid && !is_section {
+                grid_text.push_str(line);
+            }
+            grid_text.push('\n');
+
+            if is_section {
+                section = match &trimmed[1..trimmed.len() - 1] {
+                    "grid" => Section::Grid,
+                    "words" => Section::Words,
+                    "answers" => Section::Answers,
+                    other => return Err(invalid(i, format!("unknown section [{}]", other))),
+                };
+                if seen.contains(&section) {
+                    return Err(invalid(i, format!("repeated section {}", trimmed)));
+                }
+                if version.is_none() {
+                    return Err(invalid(
+                        i,
+                        "expected version before the first section".to_string(),
+                    ));
+                }
+                seen.push(section);
+                continue;
+            }
+
+            match section {
+                Section::Header => {
+                    if trimmed.is_empty() {
+                        continue;
+                    }
+
+                    let (key, value) = match trimmed.find(':') {
+                        Some(colon) => (trimmed[..colon].trim(), trimmed[colon + 1..].trim()),
+                        None => return Err(invalid(i, "expected 'key: value'".to_string())),
+                    };
+
+                    match key {
+                        "version" => {
+                            let v = value
+                                .parse::<u32>()
+                                .map_err(|_| invalid(i, format!("invalid version '{}'", value)))?;
+                            if v == 0 || v > PUZZLE_FORMAT_VERSION {
+                                return Err(WordsearchError::UnsupportedPuzzleVersion(v));
+                            }
+                            version = Some(v);
+                        }
+                        "title" => metadata.title = Some(unescape_header_value(value)),
+                        "author" => metadata.author = Some(unescape_header_value(value)),
+                        "seed" => {
+                            metadata.seed = Some(
+                                value
+                                    .parse()
+                                    .map_err(|_| invalid(i, format!("invalid seed '{}'", value)))?,
+                            )
+                        }
+                        "size" => {
+                            let mut dims = value.split('x').map(|n| n.trim().parse::<usize>());
+                            match (dims.next(), dims.next(), dims.next()) {
+                                (Some(Ok(rows)), Some(Ok(cols)), None) => {
+                                    size = Some((i, rows, cols))
+                                }
+                                _ => {
+                                    return Err(invalid(
+                                        i,
+                                        format!("invalid size '{}', expected ROWSxCOLS", value),
+                                    ))
+                                }
+                            }
+                        }
+                        "topology" => {
+                            topology = value.parse().map_err(|e| invalid(i, format!("{}", e)))?
+                        }
+                        "directions" => {
+                            metadata.directions = Some(
+                                value
+                                    .split(',')
+                                    .filter(|d| !d.trim().is_empty())
+                                    .map(|d| d.parse().map_err(|e| invalid(i, format!("{}", e))))
+                                    .collect::<Result<Vec<Direction>, _>>()?,
+                            )
+                        }
+                        other => return Err(invalid(i, format!("unknown key '{}'", other))),
+                    }
+                }
+                Section::Grid => {}
+                Section::Words => {
+                    if !trimmed.is_empty() {
+                        words.push(trimmed.to_string());
+                    }
+                }
+                Section::Answers => {
+                    if !trimmed.is_empty() {
+                        answers.push(parse_answer(trimmed).map_err(|m| invalid(i, m))?);
+                    }
+                }
+            }
+        }
+
+        if version.is_none() {
+            return Err(invalid(0, "expected version".to_string()));
+        }
+        for (name, s) in &[
+            ("grid", Section::Grid),
+            ("words", Section::Words),
+            ("answers", Section::Answers),
+        ] {
+            if !seen.contains(s) {
+                return Err(WordsearchError::MissingPuzzleSection(name.to_string()));
+            }
+        }
+
+        let grid = grid_text.parse::<Grid>()?.with_topology(topology);
+
+        if let Some((i, rows, cols)) = size {
+            if (rows, cols) != (grid.row_count(), grid.col_count()) {
+                return Err(invalid(
+                    i,
+                    format!(
+                        "size is {}x{} but the grid is {}x{}",
+                        rows,
+                        cols,
+                        grid.row_count(),
+                        grid.col_count()
+                    ),
+                ));
+            }
+        }
+
+        let puzzle = Puzzle {
+            grid,
+            words,
+            answers,
+            metadata,
+        };
+        puzzle.validate()?;
+        Ok(puzzle)
+    }
+}
+
+/// Parses a location in the form the answer key is written in, `word row,col
+/// row,col direction`, e.g. `cat 0,0 0,2 right`. The word may contain spaces.
+impl FromStr for WordLocation {
+    type Err = WordsearchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_answer(s.trim()).map_err(|message| WordsearchError::InvalidWordLocation {
+            location: s.to_string(),
+            message,
+        })
+    }
+}
+
+// Escapes the line breaks in a header value, so that it stays on one line.
+fn escape_header_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape_header_value(value: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('\\') => unescaped.push('\\'),
+            // Any other backslash is kept as it is.
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+// Parses an answer in the form `word row,col row,col direction`. The word may contain spaces.
+fn parse_answer(line: &str) -> Result<WordLocation, String> {
+    let mut parts = line.rsplitn(4, char::is_whitespace).map(|p| p.trim());
+    let direction = parts.next().unwrap_or_default();
+    let end_cell = parts.next().unwrap_or_default();
+    let start_cell = parts.next().unwrap_or_default();
+    let word = parts.next().unwrap_or_default();
+
+    if word.is_empty() {
+        return Err("expected 'word row,col row,col direction'".to_string());
+    }
+
+    Ok(WordLocation {
+        word: word.to_string(),
+        start_cell: parse_cell(start_cell)?,
+        end_cell: parse_cell(end_cell)?,
+        direction: direction.parse().map_err(|e| format!("{}", e))?,
+    })
+}
+
+fn parse_cell(s: &str) -> Result<Cell, String> {
+    let invalid = || format!("invalid cell '{}', expected row,col", s);
+    let comma = s.find(',').ok_or_else(invalid)?;
+
+    Ok(Cell {
+        row: s[..comma].parse().map_err(|_| invalid())?,
+        col: s[comma + 1..].parse().map_err(|_| invalid())?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_grid_from_seed, solve_grid_all_occurrences};
+
+    fn puzzle() -> Puzzle {
+        let grid: Grid = "cat\nxox\ngox\n".parse().unwrap();
+        let answers = solve_grid_all_occurrences(&grid, &["cat", "tog"]).unwrap();
+
+        Puzzle {
+            grid,
+            words: vec!["cat".to_string(), "tog".to_string()],
+            answers,
+            metadata: PuzzleMetadata {
+                title: Some("Animals".to_string()),
+                author: None,
+                seed: Some(42),
+                directions: None,
+            },
+        }
+    }
+
+    const PUZZLE_TEXT: &str = "version: 1
+title: Animals
+seed: 42
+size: 3x3
+
+[grid]
+c a t
+x o x
+g o x
+
+[words]
+cat
+tog
+
+[answers]
+cat 0,0 0,2 right
+tog 0,2 2,0 down-left
+";
+
+    #[test]
+    fn test_puzzle_text() {
+        assert_eq!(puzzle().to_string(), PUZZLE_TEXT);
+        assert_eq!(PUZZLE_TEXT.parse::<Puzzle>().unwrap(), puzzle());
+
+        let generated = generate_grid_from_seed(8, 8, &["icecream", "kiwi", "mango", "pear"], 7);
+        let mut generated = Puzzle::new(
+            generated.unwrap().grid,
+            &["ice cream", "kiwi", "mango", "pear"],
+        )
+        .unwrap();
+        // The answer key records the words as they are spelled in the word list.
+        assert!(generated.answers.iter().any(|a| a.word == "ice cream"));
+        generated.metadata = PuzzleMetadata {
+            title: Some("Fruit: and more".to_string()),
+            author: Some("Rob".to_string()),
+            seed: Some(7),
+            directions: Some(vec![Direction::Right, Direction::UpLeft]),
+        };
+        assert_eq!(generated.to_string().parse::<Puzzle>().unwrap(), generated);
+
+        // Line breaks in the title and author stay in the header.
+        generated.metadata.title = Some("a\n[grid]\r\nb\\n c:\\d".to_string());
+        generated.metadata.author = Some("\\".to_string());
+        let text = generated.to_string();
+        assert!(text.contains("title: a\\n[grid]\\r\\nb\\\\n c:\\\\d\nauthor: \\\\\n"));
+        assert_eq!(text.parse::<Puzzle>().unwrap(), generated);
+        assert_eq!(unescape_header_value("c:\\d\\"), "c:\\d\\");
+
+        // Wrapped words are only found again if the topology is kept.
+        let grid = "atc\nxox\nxgx\n"
+            .parse::<Grid>()
+            .unwrap()
+            .with_topology(Topology::Toroidal);
+        let wrapped = Puzzle::new(grid, &["cat"]).unwrap();
+        let text = wrapped.to_string();
+        assert!(text.contains("size: 3x3\ntopology: toroidal\n"));
+        assert!(text.contains("cat 0,2 0,1 right"));
+        assert_eq!(text.parse::<Puzzle>().unwrap(), wrapped);
+        assert_eq!(
+            text.replace("topology: toroidal\n", "")
+                .parse::<Puzzle>()
+                .unwrap_err(),
+            WordsearchError::IncorrectAnswer {
+                word: "cat".to_string(),
+                start_cell: Cell { row: 0, col: 2 },
+                end_cell: Cell { row: 0, col: 1 }
+            }
+        );
+    }
+
+    #[test]
+    fn test_puzzle_text_errors() {
+        let parse = |text: &str| text.parse::<Puzzle>().unwrap_err();
+        let invalid = |line: usize, message: &str| WordsearchError::InvalidPuzzleLine {
+            line,
+            message: message.to_string(),
+        };
+
+        assert_eq!(
+            parse(&PUZZLE_TEXT.replace("version: 1", "version: 2")),
+            WordsearchError::UnsupportedPuzzleVersion(2)
+        );
+        assert_eq!(
+            parse(&PUZZLE_TEXT.replace("version: 1\n", "")),
+            invalid(5, "expected version before the first section")
+        );
+        assert_eq!(
+            parse(&PUZZLE_TEXT.replace("seed: 42", "colour: red")),
+            invalid(3, "unknown key 'colour'")
+        );
+        assert_eq!(
+            parse(&PUZZLE_TEXT.replace("size: 3x3", "size: 3x4")),
+            invalid(4, "size is 3x4 but the grid is 3x3")
+        );
+        assert_eq!(
+            parse(&PUZZLE_TEXT.replace("size: 3x3", "size: 3x3\ntopology: flat")),
+            invalid(5, "unknown topology: flat")
+        );
+        assert_eq!(
+            parse(&PUZZLE_TEXT.replace("x o x", "x o")),
+            WordsearchError::RaggedLine {
+                line: 8,
+                column: 4,
+                expected: 3,
+                found: 2
+            }
+        );
+        assert_eq!(
+            parse(&PUZZLE_TEXT.replace("2,0 down-left", "2,0")),
+            invalid(17, "expected 'word row,col row,col direction'")
+        );
+        assert_eq!(
+            parse(&PUZZLE_TEXT.replace("0,2 right", "0,x right")),
+            invalid(16, "invalid cell '0,x', expected row,col")
+        );
+        assert_eq!(
+            parse(&PUZZLE_TEXT.replace("[answers]", "[answer]")),
+            invalid(15, "unknown section [answer]")
+        );
+        assert_eq!(
+            parse(PUZZLE_TEXT.split("[answers]").next().unwrap()),
+            WordsearchError::MissingPuzzleSection("answers".to_string())
+        );
+
+        // The answer key must match the solver.
+        assert_eq!(
+            parse(&PUZZLE_TEXT.replace("tog 0,2 2,0 down-left", "tog 2,0 0,2 up-right")),
+            WordsearchError::IncorrectAnswer {
+                word: "tog".to_string(),
+                start_cell: Cell { row: 2, col: 0 },
+                end_cell: Cell { row: 0, col: 2 }
+            }
+        );
+        assert_eq!(
+            parse(&PUZZLE_TEXT.replace("cat 0,0 0,2 right", "cat 0,2 0,0 right")),
+            WordsearchError::IncorrectAnswer {
+                word: "cat".to_string(),
+                start_cell: Cell { row: 0, col: 2 },
+                end_cell: Cell { row: 0, col: 0 }
+            }
+        );
+        assert_eq!(
+            parse(&PUZZLE_TEXT.replace("cat 0,0 0,2 right\n", "")),
+            WordsearchError::MissingAnswer {
+                word: "cat".to_string(),
+                start_cell: Cell { row: 0, col: 0 },
+                end_cell: Cell { row: 0, col: 2 }
+            }
+        );
+    }
+
+    #[test]
+    fn test_word_location_from_str() {
+        assert_eq!(
+            " ice cream 1,4 1,0 left".parse(),
+            Ok(WordLocation {
+                word: "ice cream".to_string(),
+                start_cell: Cell { row: 1, col: 4 },
+                end_cell: Cell { row: 1, col: 0 },
+                direction: Direction::Left,
+            })
+        );
+        assert_eq!(
+            "cat 0,0 0;2 right".parse::<WordLocation>(),
+            Err(WordsearchError::InvalidWordLocation {
+                location: "cat 0,0 0;2 right".to_string(),
+                message: "invalid cell '0;2', expected row,col".to_string(),
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_puzzle_json() {
+        let json = serde_json::to_string(&puzzle()).unwrap();
+        assert_eq!(
+            json,
+            r#"{"grid":["cat","xox","gox"],"words":["cat","tog"],"answers":[{"word":"cat","start_cell":{"row":0,"col":0},"end_cell":{"row":0,"col":2},"direction":"right"},{"word":"tog","start_cell":{"row":0,"col":2},"end_cell":{"row":2,"col":0},"direction":"down-left"}],"metadata":{"title":"Animals","seed":42}}"#
+        );
+        assert_eq!(serde_json::from_str::<Puzzle>(&json).unwrap(), puzzle());
+
+        // Metadata is optional.
+        let puzzle = serde_json::from_str::<Puzzle>(
+            r#"{"grid":["ab"],"words":["ab"],"answers":[{"word":"ab","start_cell":{"row":0,"col":0},"end_cell":{"row":0,"col":1},"direction":"right"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(puzzle.metadata, PuzzleMetadata::default());
+        assert_eq!(puzzle.answers[0].end_cell, Cell { row: 0, col: 1 });
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_generated_puzzle_round_trip() {
+        let words = ["kiwi", "mango", "pear", "plum"];
+        let generated = generate_grid_from_seed(8, 8, &words, 7).unwrap();
+        let mut puzzle = Puzzle::new(generated.grid, &words).unwrap();
+        assert_eq!(puzzle.answers, generated.placements);
+        puzzle.metadata.directions = Some(vec![Direction::DownRight]);
+
+        let json = serde_json::to_string_pretty(&puzzle).unwrap();
+        assert_eq!(serde_json::from_str::<Puzzle>(&json).unwrap(), puzzle);
+
+        let grid = puzzle.grid.with_topology(Topology::Toroidal);
+        let json = serde_json::to_string(&grid).unwrap();
+        assert!(json.starts_with(r#"{"rows":[""#));
+        assert!(json.ends_with(r#""],"topology":"toroidal"}"#));
+        assert_eq!(serde_json::from_str::<Grid>(&json).unwrap(), grid);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_errors() {
+        assert_eq!(
+            serde_json::from_str::<Grid>(r#"["éa","b"]"#)
+                .unwrap_err()
+                .to_string(),
+            "grid row 1 has 1 columns but expected 2"
+        );
+        assert!(serde_json::from_str::<Direction>(r#""sideways""#).is_err());
+
+        for d in Direction::iterator() {
+            let json = serde_json::to_string(d).unwrap();
+            assert_eq!(json, format!("\"{}\"", d));
+            assert_eq!(serde_json::from_str::<Direction>(&json).unwrap(), *d);
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use super::{escape_xml, HIGHLIGHT_COLOURS};
+use crate::{Grid, WordLocation, EMPTY_CHAR, MASKED_CHAR};
+use std::fmt;
+
+const STYLE: &str = r#"
+body { font-family: sans-serif; display: flex; flex-wrap: wrap; gap: 2em; padding: 1em; }
+table { border-collapse: collapse; user-select: none; touch-action: none; }
+td { width: 2em; height: 2em; text-align: center; font-size: 1.4em; text-transform: uppercase; cursor: pointer; border-radius: 1em; }
+td.masked { visibility: hidden; }
+td.selected { box-shadow: inset 0 0 0 2em rgba(255, 200, 0, 0.5); }
+ul { list-style: none; padding: 0; columns: 2; }
+li.found { text-decoration: line-through; color: #888; }
+#done { display: none; font-weight: bold; }
+"#;
+
+// Marks a word when the drag starts and ends on the same cells as one of the answers,
+// in either direction. The answer's own cells are coloured, so that a word that wraps
+// around the edges of the grid is marked where it is rather than along the drag.
+const SCRIPT: &str = r#"
+(function () {
+  var answers = ANSWERS;
+  var colours = COLOURS;
+  var table = document.getElementById("grid");
+  var start = null;
+  var selected = [];
+  var found = 0;
+
+  function cell(r, c) {
+    var row = table.rows[r];
+    return row ? row.cells[c] : undefined;
+  }
+
+  function position(td) {
+    return [td.parentNode.rowIndex, td.cellIndex];
+  }
+
+  // The cells in a straight line from start to end, or none if they are not in a line.
+  function line(from, to) {
+    var dr = to[0] - from[0], dc = to[1] - from[1];
+    var length = Math.max(Math.abs(dr), Math.abs(dc));
+    if (dr !== 0 && dc !== 0 && Math.abs(dr) !== Math.abs(dc)) {
+      return [];
+    }
+    var cells = [];
+    for (var i = 0; i <= length; i++) {
+      cells.push(cell(from[0] + Math.sign(dr) * i, from[1] + Math.sign(dc) * i));
+    }
+    return cells;
+  }
+
+  function select(cells) {
+    selected.forEach(function (td) { td.classList.remove("selected"); });
+    selected = cells;
+    selected.forEach(function (td) { td.classList.add("selected"); });
+  }
+
+  function same(a, b) {
+    return a[0] === b[0] && a[1] === b[1];
+  }
+
+  table.addEventListener("pointerdown", function (e) {
+    if (e.target.tagName !== "TD" || e.target.classList.contains("masked")) return;
+    start = position(e.target);
+    select([e.target]);
+  });
+
+  table.addEventListener("pointermove", function (e) {
+    if (start === null) return;
+    var td = document.elementFromPoint(e.clientX, e.clientY);
+    if (td && td.tagName === "TD" && table.contains(td)) {
+      select(line(start, position(td)));
+    }
+  });
+
+  document.addEventListener("pointerup", function () {
+    if (start === null) return;
+    // The drag is over even if it was not in a line and so selected nothing.
+    var dragged = selected;
+    select([]);
+    start = null;
+    if (dragged.length === 0) return;
+    var from = position(dragged[0]), to = position(dragged[dragged.length - 1]);
+    answers.forEach(function (a) {
+      if (a.marked || !((same(a.start, from) && same(a.end, to)) || (same(a.start, to) && same(a.end, from)))) return;
+      a.marked = true;
+      var colour = colours[found % colours.length];
+      a.cells.forEach(function (c) { cell(c[0], c[1]).style.background = colour + "59"; });
+      document.querySelectorAll("li").forEach(function (li) {
+        if (li.dataset.word === a.word) li.classList.add("found");
+      });
+      found++;
+    });
+    if (document.querySelectorAll("li:not(.found)").length === 0) {
+      document.getElementById("done").style.display = "block";
+    }
+  });
+})();
+"#;
+
+/// Renders the grid as a single, self-contained HTML page.
+///
+/// Players drag across the letters to mark a word, which is struck off the word
+/// bank when the drag starts and ends on the cells of one of the `answers`.
+pub fn render_html(grid: &Grid, words: &[&str], answers: &[WordLocation], title: &str) -> String {
+    Html {
+        grid,
+        words,
+        answers,
+        title,
+    }
+    .to_string()
+}
+
+// The HTML page for a grid, written out with `Display`.
+struct Html<'a> {
+    grid: &'a Grid,
+    words: &'a [&'a str],
+    answers: &'a [WordLocation],
+    title: &'a str,
+}
+
+impl fmt::Display for Html<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Html {
+            grid,
+            words,
+            answers,
+            title,
+        } = *self;
+        writeln!(f, "<!DOCTYPE html>")?;
+        writeln!(f, r#"<html lang="en">"#)?;
+        writeln!(f, "<head>")?;
+        writeln!(f, r#"<meta charset="utf-8">"#)?;
+        writeln!(f, "<title>{}</title>", escape_xml(title))?;
+        writeln!(f, "<style>{}</style>", STYLE)?;
+        writeln!(f, "</head>")?;
+        writeln!(f, "<body>")?;
+        writeln!(f, "<h1 style=\"width: 100%\">{}</h1>", escape_xml(title))?;
+
+        writeln!(f, r#"<table id="grid">"#)?;
+        for row in &grid.chars {
+            write!(f, "<tr>")?;
+            for c in row {
+                if *c == MASKED_CHAR {
+                    write!(f, r#"<td class="masked"></td>"#)?;
+                    continue;
+                }
+
+                let letter = if *c == EMPTY_CHAR {
+                    String::new()
+                } else {
+                    escape_xml(&c.to_string())
+                };
+                write!(f, "<td>{}</td>", letter)?;
+            }
+            writeln!(f, "</tr>")?;
+        }
+        writeln!(f, "</table>")?;
+
+        writeln!(f, "<div>")?;
+        writeln!(f, "<ul>")?;
+        for w in words {
+            writeln!(f, r#"<li data-word="{w}">{w}</li>"#, w = escape_xml(w))?;
+        }
+        writeln!(f, "</ul>")?;
+        writeln!(f, r#"<p id="done">All words found!</p>"#)?;
+        writeln!(f, "</div>")?;
+
+        let answers_json = answers
+            .iter()
+            .map(|a| {
+                // Only cells in the grid are coloured, in case the answer runs off it.
+                let cells = a
+                    .cells(grid)
+                    .filter(|c| grid.contains(c))
+                    .map(|c| format!("[{},{}]", c.row, c.col))
+                    .collect::<Vec<_>>();
+                format!(
+                    r#"{{"word":{},"start":[{},{}],"end":[{},{}],"cells":[{}]}}"#,
+                    json_string(&a.word),
+                    a.start_cell.row,
+                    a.start_cell.col,
+                    a.end_cell.row,
+                    a.end_cell.col,
+                    cells.join(",")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let colours_json = HIGHLIGHT_COLOURS
+            .iter()
+            .map(|c| json_string(c))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        // The placeholders are only looked for in SCRIPT itself, not in the answers
+        // put in place of one of them, which may contain the other.
+        let script = SCRIPT
+            .split("ANSWERS")
+            .map(|part| part.replace("COLOURS", &format!("[{}]", colours_json)))
+            .collect::<Vec<_>>()
+            .join(&format!("[{}]", answers_json));
+        writeln!(f, "<script>{}</script>", script)?;
+        writeln!(f, "</body>")?;
+        writeln!(f, "</html>")
+    }
+}
+
+// A JSON string literal that is also safe to embed in a <script> element.
+fn json_string(s: &str) -> String {
+    let mut json = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '<' => json.push_str("\\u003c"),
+            '>' => json.push_str("\\u003e"),
+            '&' => json.push_str("\\u0026"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{solve_grid_all_occurrences, Topology};
+
+    #[test]
+    fn test_render_html() {
+        let grid: Grid = "cat\nxox\ng<x\n".parse().unwrap();
+        let words = ["cat", "tog"];
+        let answers = solve_grid_all_occurrences(&grid, &words).unwrap();
+
+        let html = render_html(&grid, &words, &answers, "Animals & more");
+
+        assert!(html.starts_with("<!DOCTYPE html>\n"));
+        assert!(html.contains("<title>Animals &amp; more</title>"));
+        assert!(html.contains("<tr><td>g</td><td>&lt;</td><td>x</td></tr>"));
+        assert_eq!(html.matches("<td>").count(), 9);
+        assert!(html.contains(r#"<li data-word="tog">tog</li>"#));
+        assert!(html.contains(
+            r#"var answers = [{"word":"cat","start":[0,0],"end":[0,2],"cells":[[0,0],[0,1],[0,2]]},{"word":"tog","start":[0,2],"end":[2,0],"cells":[[0,2],[1,1],[2,0]]}];"#
+        ));
+        let wrapped = "atc\n"
+            .parse::<Grid>()
+            .unwrap()
+            .with_topology(Topology::Toroidal);
+        let answers = solve_grid_all_occurrences(&wrapped, &["cat"]).unwrap();
+        assert!(render_html(&wrapped, &["cat"], &answers, "Wrapped")
+            .contains(r#""cells":[[0,2],[0,0],[0,1]]"#));
+
+        let masked = render_html(&"#a\nbc\n".parse().unwrap(), &[], &[], "Shape");
+        assert!(masked.contains(r#"<tr><td class="masked"></td><td>a</td></tr>"#));
+
+        // Words are not mistaken for the placeholders in the script.
+        let grid: Grid = "COLOURS\nANSWERS\n".parse().unwrap();
+        let words = ["COLOURS", "ANSWERS"];
+        let answers = solve_grid_all_occurrences(&grid, &words).unwrap();
+        let placeholders = render_html(&grid, &words, &answers, "Placeholders");
+        assert!(placeholders.contains(r#"var answers = [{"word":"ANSWERS","#));
+        assert!(placeholders.contains(r#"{"word":"COLOURS","start":[0,0]"#));
+        assert!(placeholders.contains(r##"var colours = ["#e6194b","##));
+
+        // A drag that selects nothing still ends, so that moving the pointer
+        // afterwards does not select anything.
+        let pointerup = &html[html.find(r#""pointerup""#).unwrap()..];
+        let early_return = pointerup.find("if (dragged.length === 0) return;").unwrap();
+        assert!(pointerup[..early_return].contains("start = null;"));
+
+        // Nothing is loaded from elsewhere.
+        assert!(!html.contains("src="));
+        assert!(!html.contains("href="));
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("plain"), r#""plain""#);
+        assert_eq!(
+            json_string("</script>\"\\\n"),
+            r#""\u003c/script\u003e\"\\\u000a""#
+        );
+    }
+}
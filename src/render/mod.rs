@@ -0,0 +1,27 @@
+mod html;
+mod svg;
+mod terminal;
+
+pub use html::render_html;
+pub use svg::{render_svg, render_svg_answer_key, SvgOptions};
+pub use terminal::{render_terminal, render_terminal_paths};
+
+// Colours for highlighting words, chosen to be distinguishable when they overlap.
+const HIGHLIGHT_COLOURS: [&str; 8] = [
+    "#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4", "#42d4f4", "#f032e6", "#9a6324",
+];
+
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
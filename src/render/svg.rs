@@ -0,0 +1,280 @@
+use super::{escape_xml, HIGHLIGHT_COLOURS};
+use crate::{Cell, Direction, Grid, WordLocation, EMPTY_CHAR, MASKED_CHAR};
+use std::fmt;
+
+/// Settings for drawing a grid as SVG.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SvgOptions {
+    /// The width and height of each cell, in pixels.
+    pub cell_size: u32,
+    /// The height of the letters, in pixels.
+    pub font_size: u32,
+    /// A CSS font family, e.g. `monospace` or `"Comic Sans MS", cursive`.
+    pub font_family: String,
+    /// Whether to list the words below the grid.
+    pub word_bank: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            cell_size: 40,
+            font_size: 24,
+            font_family: "sans-serif".to_string(),
+            word_bank: true,
+        }
+    }
+}
+
+/// Draws the grid as an SVG image, with the words listed below it if
+/// `options.word_bank` is set.
+pub fn render_svg(grid: &Grid, words: &[&str], options: &SvgOptions) -> String {
+    Svg {
+        grid,
+        words,
+        answers: &[],
+        options,
+    }
+    .to_string()
+}
+
+/// As `render_svg`, with a capsule drawn around each of the answers.
+pub fn render_svg_answer_key(
+    grid: &Grid,
+    words: &[&str],
+    answers: &[WordLocation],
+    options: &SvgOptions,
+) -> String {
+    Svg {
+        grid,
+        words,
+        answers,
+        options,
+    }
+    .to_string()
+}
+
+// An SVG image of the grid, written out with `Display`.
+struct Svg<'a> {
+    grid: &'a Grid,
+    words: &'a [&'a str],
+    answers: &'a [WordLocation],
+    options: &'a SvgOptions,
+}
+
+impl fmt::Display for Svg<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Svg {
+            grid,
+            words,
+            answers,
+            options,
+        } = *self;
+        let cell_size = f64::from(options.cell_size);
+        let font_size = f64::from(options.font_size);
+        let margin = cell_size / 2.0;
+        let grid_width = grid.col_count() as f64 * cell_size;
+        let grid_height = grid.row_count() as f64 * cell_size;
+
+        // Lay the word bank out in as many columns as fit under the grid, assuming
+        // letters are roughly 0.6em wide.
+        let longest = words.iter().map(|w| w.chars().count()).max().unwrap_or(0);
+        let line_height = font_size * 1.5;
+        let column_width = (longest as f64 * 0.6 + 2.0) * font_size;
+        let per_row = ((grid_width / column_width).floor() as usize).max(1);
+        let bank_height = if options.word_bank && !words.is_empty() {
+            margin + ((words.len() + per_row - 1) / per_row) as f64 * line_height
+        } else {
+            0.0
+        };
+
+        let width = grid_width + 2.0 * margin;
+        let height = grid_height + 2.0 * margin + bank_height;
+        let centre = |c: &Cell| {
+            (
+                margin + (c.col as f64 + 0.5) * cell_size,
+                margin + (c.row as f64 + 0.5) * cell_size,
+            )
+        };
+
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = width,
+            h = height
+        )?;
+        writeln!(f, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+
+        if !answers.is_empty() {
+            // A line with round caps is a capsule, whatever the direction.
+            writeln!(
+                f,
+                r#"<g fill="none" stroke-linecap="round" stroke-opacity="0.35" stroke-width="{}">"#,
+                cell_size * 0.8
+            )?;
+            for (i, a) in answers.iter().enumerate() {
+                // A word that wraps around the edges of a toroidal grid is drawn
+                // as one capsule for each part of it.
+                let cells = a.cells(grid).collect::<Vec<_>>();
+                for (start, end) in segments(&cells, &a.direction) {
+                    let (x1, y1) = centre(&start);
+                    let (x2, y2) = centre(&end);
+                    writeln!(
+                        f,
+                        r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}"><title>{}</title></line>"#,
+                        x1,
+                        y1,
+                        x2,
+                        y2,
+                        HIGHLIGHT_COLOURS[i % HIGHLIGHT_COLOURS.len()],
+                        escape_xml(&a.word)
+                    )?;
+                }
+            }
+            writeln!(f, "</g>")?;
+        }
+
+        writeln!(
+            f,
+            r#"<g font-family="{}" font-size="{}" text-anchor="middle" dominant-baseline="central">"#,
+            escape_xml(&options.font_family),
+            font_size
+        )?;
+        for (r, row) in grid.chars.iter().enumerate() {
+            for (c, letter) in row.iter().enumerate() {
+                if *letter == EMPTY_CHAR || *letter == MASKED_CHAR {
+                    continue;
+                }
+
+                let (x, y) = centre(&Cell { row: r, col: c });
+                writeln!(
+                    f,
+                    r#"<text x="{}" y="{}">{}</text>"#,
+                    x,
+                    y,
+                    escape_xml(&letter.to_string())
+                )?;
+            }
+        }
+        writeln!(f, "</g>")?;
+
+        if bank_height > 0.0 {
+            writeln!(
+                f,
+                r#"<g font-family="{}" font-size="{}" dominant-baseline="central">"#,
+                escape_xml(&options.font_family),
+                font_size
+            )?;
+            for (i, w) in words.iter().enumerate() {
+                let x = margin + (i % per_row) as f64 * column_width;
+                let y = 2.0 * margin + grid_height + ((i / per_row) as f64 + 0.5) * line_height;
+                writeln!(f, r#"<text x="{}" y="{}">{}</text>"#, x, y, escape_xml(w))?;
+            }
+            writeln!(f, "</g>")?;
+        }
+
+        writeln!(f, "</svg>")
+    }
+}
+
+// The first and last cells of each run of cells that follow on from each
+// other in the direction without wrapping.
+fn segments(cells: &[Cell], direction: &Direction) -> Vec<(Cell, Cell)> {
+    let (dr, dc) = match direction {
+        Direction::Up => (-1, 0),
+        Direction::UpRight => (-1, 1),
+        Direction::Right => (0, 1),
+        Direction::DownRight => (1, 1),
+        Direction::Down => (1, 0),
+        Direction::DownLeft => (1, -1),
+        Direction::Left => (0, -1),
+        Direction::UpLeft => (-1, -1),
+    };
+
+    let mut segments: Vec<(Cell, Cell)> = vec![];
+    for c in cells {
+        match segments.last_mut() {
+            Some((_, end))
+                if c.row as isize - end.row as isize == dr
+                    && c.col as isize - end.col as isize == dc =>
+            {
+                *end = *c
+            }
+            _ => segments.push((*c, *c)),
+        }
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{solve_grid_all_occurrences, Topology};
+
+    fn grid() -> Grid {
+        "cat\nxox\ng<x\n".parse().unwrap()
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let options = SvgOptions {
+            cell_size: 10,
+            font_size: 8,
+            ..Default::default()
+        };
+        let svg = render_svg(&grid(), &["cat", "dog & co"], &options);
+
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="#));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<text ").count(), 9 + 2);
+        assert!(svg.contains(r#"<text x="10" y="10">c</text>"#));
+        assert!(svg.contains(r#"<text x="20" y="30">&lt;</text>"#));
+        assert!(svg.contains(">dog &amp; co</text>"));
+        assert!(!svg.contains("<line"));
+
+        let svg = render_svg(
+            &grid(),
+            &["cat"],
+            &SvgOptions {
+                word_bank: false,
+                ..options.clone()
+            },
+        );
+        assert!(svg.contains(r#"width="40" height="40""#));
+        assert!(!svg.contains(">cat<"));
+
+        // Masked out cells are left blank.
+        let grid: Grid = "#a#\nbcd\n".parse().unwrap();
+        let svg = render_svg(&grid, &[], &options);
+        assert_eq!(svg.matches("<text ").count(), 4);
+        assert!(!svg.contains(">#<"));
+    }
+
+    #[test]
+    fn test_render_svg_answer_key() {
+        let words = ["cat", "tog"];
+        let answers = solve_grid_all_occurrences(&grid(), &words).unwrap();
+        assert_eq!(answers[1].direction, Direction::DownLeft);
+
+        let svg = render_svg_answer_key(&grid(), &words, &answers, &SvgOptions::default());
+
+        assert_eq!(svg.matches("<line ").count(), 2);
+        assert!(svg.contains(
+            r##"<line x1="40" y1="40" x2="120" y2="40" stroke="#e6194b"><title>cat</title></line>"##
+        ));
+        assert!(svg.contains(r#"<line x1="120" y1="40" x2="40" y2="120""#));
+        // Capsules are drawn beneath the letters.
+        assert!(svg.find("<line ").unwrap() < svg.find("<text ").unwrap());
+
+        // Words that wrap around the edges are split where they wrap.
+        let grid = "atc\nxxx\n"
+            .parse::<Grid>()
+            .unwrap()
+            .with_topology(Topology::Toroidal);
+        let answers = solve_grid_all_occurrences(&grid, &["cat"]).unwrap();
+        let svg = render_svg_answer_key(&grid, &["cat"], &answers, &SvgOptions::default());
+        assert_eq!(svg.matches("<line ").count(), 2);
+        assert!(svg.contains(r#"<line x1="120" y1="40" x2="120" y2="40""#));
+        assert!(svg.contains(r#"<line x1="40" y1="40" x2="80" y2="40""#));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{Cell, Grid, WordLocation, WordPath, MASKED_CHAR};
+
+// ANSI foreground colours, bold so that they stand out from the other letters.
+const ANSI_COLOURS: [&str; 12] = [
+    "1;31", "1;32", "1;33", "1;34", "1;35", "1;36", "1;91", "1;92", "1;93", "1;94", "1;95", "1;96",
+];
+// Cells in more than one word are shown in reverse video.
+const ANSI_OVERLAP: &str = "1;7";
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Renders the grid once with every answer highlighted, followed by a key of the words.
+///
+/// With `colour`, each word is drawn in its own ANSI colour and cells shared by
+/// more than one word are shown in reverse video. Without it, letters in a word
+/// are upper case, everything else is lower case, and shared cells are bracketed.
+pub fn render_terminal(grid: &Grid, answers: &[WordLocation], colour: bool) -> String {
+    let answers = answers
+        .iter()
+        .map(|a| (a.word.as_str(), a.cells(grid).collect()))
+        .collect::<Vec<_>>();
+    render(grid, &answers, colour)
+}
+
+/// As `render_terminal`, for words found along bent paths.
+pub fn render_terminal_paths(grid: &Grid, answers: &[WordPath], colour: bool) -> String {
+    let answers = answers
+        .iter()
+        .map(|a| (a.word.as_str(), a.cells.clone()))
+        .collect::<Vec<_>>();
+    render(grid, &answers, colour)
+}
+
+// Renders each answer given as its word and its cells.
+fn render(grid: &Grid, answers: &[(&str, Vec<Cell>)], colour: bool) -> String {
+    // The index of the last answer covering each cell, and how many answers cover it.
+    let mut covering: Vec<Vec<(Option<usize>, usize)>> =
+        vec![vec![(None, 0); grid.col_count()]; grid.row_count()];
+
+    // Cells outside the grid, from answers that run off it, are skipped.
+    for (i, (_, cells)) in answers.iter().enumerate() {
+        for c in cells {
+            if let Some(entry) = covering.get_mut(c.row).and_then(|r| r.get_mut(c.col)) {
+                *entry = (Some(i), entry.1 + 1);
+            }
+        }
+    }
+
+    let mut rendered = String::new();
+    for (row, row_covering) in grid.chars.iter().zip(&covering) {
+        let cells = row
+            .iter()
+            .zip(row_covering)
+            .map(|(letter, (answer, count))| match (colour, answer) {
+                // Masked out cells are left blank.
+                (true, _) if *letter == MASKED_CHAR => " ".to_string(),
+                (false, _) if *letter == MASKED_CHAR => "   ".to_string(),
+                (_, None) => {
+                    if colour {
+                        letter.to_string()
+                    } else {
+                        format!(" {} ", single_char_case(*letter, false))
+                    }
+                }
+                (true, Some(i)) => {
+                    let code = if *count > 1 {
+                        ANSI_OVERLAP
+                    } else {
+                        ANSI_COLOURS[i % ANSI_COLOURS.len()]
+                    };
+                    format!("\x1b[{}m{}{}", code, letter, ANSI_RESET)
+                }
+                (false, Some(_)) => {
+                    let letter = single_char_case(*letter, true);
+                    if *count > 1 {
+                        format!("[{}]", letter)
+                    } else {
+                        format!(" {} ", letter)
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let separator = if colour { " " } else { "" };
+        rendered.push_str(cells.join(separator).trim_end());
+        rendered.push('\n');
+    }
+
+    if !answers.is_empty() {
+        rendered.push('\n');
+        for (i, (word, _)) in answers.iter().enumerate() {
+            if colour {
+                rendered.push_str(&format!(
+                    "\x1b[{}m{}{}\n",
+                    ANSI_COLOURS[i % ANSI_COLOURS.len()],
+                    word,
+                    ANSI_RESET
+                ));
+            } else {
+                rendered.push_str(&format!("{}\n", word));
+            }
+        }
+    }
+
+    rendered
+}
+
+// Changes the case of the letter, unless that would take more than one char
+// (e.g. ß becomes SS) and so misalign the grid.
+fn single_char_case(letter: char, upper: bool) -> char {
+    let mut changed = if upper {
+        letter.to_uppercase().collect::<Vec<_>>()
+    } else {
+        letter.to_lowercase().collect::<Vec<_>>()
+    };
+
+    match changed.len() {
+        1 => changed.remove(0),
+        _ => letter,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{solve_grid_all_occurrences, solve_grid_snake};
+
+    #[test]
+    fn test_render_terminal() {
+        let grid: Grid = "cat\nxox\ngßx\n".parse().unwrap();
+        let answers = solve_grid_all_occurrences(&grid, &["cat", "tog"]).unwrap();
+
+        assert_eq!(
+            render_terminal(&grid, &answers, false),
+            " C  A [T]\n x  O  x\n G  ß  x\n\ncat\ntog\n"
+        );
+
+        assert_eq!(
+            render_terminal(&grid, &answers, true),
+            "\x1b[1;31mc\x1b[0m \x1b[1;31ma\x1b[0m \x1b[1;7mt\x1b[0m\n\
+             x \x1b[1;32mo\x1b[0m x\n\
+             \x1b[1;32mg\x1b[0m ß x\n\
+             \n\
+             \x1b[1;31mcat\x1b[0m\n\
+             \x1b[1;32mtog\x1b[0m\n"
+        );
+
+        assert_eq!(render_terminal(&grid, &[], true), grid.to_string());
+
+        // Masked out cells are left blank.
+        let grid: Grid = "#at\nxo#\n".parse().unwrap();
+        let answers = solve_grid_all_occurrences(&grid, &["at"]).unwrap();
+        assert_eq!(
+            render_terminal(&grid, &answers, false),
+            "    A  T\n x  o\n\nat\n"
+        );
+        assert_eq!(render_terminal(&grid, &[], true), "  a t\nx o\n");
+
+        let grid: Grid = "cax\nxto\n".parse().unwrap();
+        let answers = solve_grid_snake(&grid, &["cat", "to"]).unwrap();
+        assert_eq!(
+            render_terminal_paths(&grid, &answers, false),
+            " C  A  x\n x [T] O\n\ncat\nto\n"
+        );
+
+        // Answers that run off the grid are only drawn where they are in it.
+        let off_grid = [WordPath {
+            word: "tot".to_string(),
+            cells: vec![
+                Cell { row: 1, col: 1 },
+                Cell { row: 1, col: 2 },
+                Cell { row: 1, col: 3 },
+            ],
+        }];
+        assert_eq!(
+            render_terminal_paths(&grid, &off_grid, false),
+            " c  a  x\n x  T  O\n\ntot\n"
+        );
+        let off_grid = ["tox 1,1 1,3 right".parse().unwrap()];
+        assert_eq!(
+            render_terminal(&grid, &off_grid, false),
+            " c  a  x\n x  t  o\n\ntox\n"
+        );
+    }
+}
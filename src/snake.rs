@@ -0,0 +1,491 @@
+use crate::fill::Filler;
+use crate::normalize::spellings_by_normalized_word;
+use crate::solver::validate_solver_words;
+use crate::{
+    split_pinned_words, starting_grid, validate_generation_words, validate_unique_words, Cell,
+    Direction, GenerationOptions, Grid, Normalization, WordsearchError, EMPTY_CHAR,
+    MAX_REFILL_ATTEMPTS,
+};
+use itertools::iproduct;
+use rand::prelude::SliceRandom;
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+// How many times the generator starts again from an empty grid before giving up.
+const MAX_PLACEMENT_ATTEMPTS: usize = 1000;
+// How many cells the generator tries to extend a word's path into before
+// giving up on the word.
+const MAX_PLACEMENT_STEPS: usize = 10_000;
+
+/// A word read along a path of cells, as in Boggle. Unlike a `WordLocation`,
+/// the path may bend at every letter.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WordPath {
+    pub word: String,
+    /// The cell of each letter of the word in turn. Each cell is one of the
+    /// eight neighbours of the cell before, and no cell is used twice.
+    pub cells: Vec<Cell>,
+}
+
+impl WordPath {
+    // Whether both paths are the same word in the same cells, in any order.
+    fn covers_same_cells(&self, other: &WordPath) -> bool {
+        self.word == other.word && sorted_cells(&self.cells) == sorted_cells(&other.cells)
+    }
+}
+
+fn sorted_cells(cells: &[Cell]) -> Vec<Cell> {
+    let mut sorted = cells.to_vec();
+    sorted.sort();
+    sorted
+}
+
+/// Finds every path that spells each of the words, where each letter is one of
+/// the eight neighbours of the letter before and no cell is used twice.
+///
+/// Paths that spell a word through the same cells are only reported once, as
+/// the first of them in order, e.g. "noon" is not reported again read backwards.
+/// The paths are sorted.
+pub fn solve_grid_snake(grid: &Grid, words: &[&str]) -> Result<Vec<WordPath>, WordsearchError> {
+    validate_solver_words(words, 1)?;
+
+    let mut found = vec![];
+    for word in words {
+        let letters = word.chars().collect::<Vec<_>>();
+
+        let mut paths = vec![];
+        for (row, col) in iproduct!(0..grid.row_count(), 0..grid.col_count()) {
+            find_paths(grid, &letters, &mut vec![Cell { row, col }], &mut paths);
+        }
+        paths.sort();
+
+        let mut seen = BTreeSet::new();
+        found.extend(
+            paths
+                .into_iter()
+                .filter(|p| seen.insert(sorted_cells(p)))
+                .map(|cells| WordPath {
+                    word: word.to_string(),
+                    cells,
+                }),
+        );
+    }
+
+    found.sort();
+    found.dedup();
+    Ok(found)
+}
+
+/// As `solve_grid_snake`, after normalizing both the grid and the words.
+///
+/// Each path reports the word as it was spelled in `words`, as with `solve_normalized`.
+pub fn solve_snake_normalized(
+    grid: &Grid,
+    words: &[&str],
+    normalization: &Normalization,
+) -> Result<Vec<WordPath>, WordsearchError> {
+    let spellings = spellings_by_normalized_word(words, normalization)?;
+
+    let normalized_words = spellings.keys().map(|w| w.as_str()).collect::<Vec<_>>();
+    let found = solve_grid_snake(&normalization.normalize_grid(grid), &normalized_words)?;
+
+    let mut located = Vec::new();
+    for p in found {
+        for spelling in &spellings[&p.word] {
+            located.push(WordPath {
+                word: spelling.to_string(),
+                cells: p.cells.clone(),
+            });
+        }
+    }
+
+    located.sort();
+    Ok(located)
+}
+
+// Adds every way of finishing the word from the path so far to `found`.
+fn find_paths(grid: &Grid, letters: &[char], path: &mut Vec<Cell>, found: &mut Vec<Vec<Cell>>) {
+    let cell = path[path.len() - 1];
+    if grid.value_at_cell(&cell) != letters[path.len() - 1] {
+        return;
+    }
+
+    if path.len() == letters.len() {
+        found.push(path.clone());
+        return;
+    }
+
+    for next in neighbours(grid, &cell, Direction::iterator().as_slice()) {
+        if !path.contains(&next) {
+            path.push(next);
+            find_paths(grid, letters, path, found);
+            path.pop();
+        }
+    }
+}
+
+// The cells next to the cell in the directions. In a small toroidal grid, the
+// same cell can be next to it in more than one direction, but is only listed once.
+fn neighbours(grid: &Grid, cell: &Cell, directions: &[Direction]) -> Vec<Cell> {
+    let mut neighbours = directions
+        .iter()
+        .filter_map(|d| grid.neighbor(cell, d))
+        .filter(|n| n != cell)
+        .collect::<Vec<_>>();
+    neighbours.sort();
+    neighbours.dedup();
+    neighbours
+}
+
+/// A generated wordsearch whose words bend, along with the path of every placed word.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GeneratedSnakePuzzle {
+    /// The puzzle, with every cell not used by a word filled with a random character.
+    pub grid: Grid,
+    /// The placed words only; every other cell is empty or masked out.
+    pub placement_grid: Grid,
+    /// The answer key, sorted in the same order as `solve_grid_snake` returns its results.
+    pub placements: Vec<WordPath>,
+}
+
+/// Generates a grid in which each word is placed along a path that may bend at
+/// every letter, to be found with `solve_grid_snake`.
+///
+/// Each step along a placed path is in one of `options.directions`, so the
+/// default of all eight lets words bend any way. The rest of the options are
+/// used as in `generate_grid_with_options`. A word may be as long as there are
+/// open cells in the grid.
+pub fn generate_snake_grid_with_options<R: Rng + ?Sized>(
+    rows: usize,
+    cols: usize,
+    words: &[&str],
+    options: &GenerationOptions,
+    rng: &mut R,
+) -> Result<GeneratedSnakePuzzle, WordsearchError> {
+    let cells = iproduct!(0..rows, 0..cols)
+        .map(|(r, c)| Cell { row: r, col: c })
+        .filter(|c| options.mask.as_ref().map_or(true, |m| m.is_open(c)))
+        .collect::<Vec<_>>();
+
+    let (all_words, unpinned_words) = split_pinned_words(words, options);
+
+    validate_generation_words(rows, cols, &all_words, options, cells.len())?;
+
+    if options.ensure_unique {
+        validate_unique_words(&all_words)?;
+    }
+
+    let filler = Filler::new(&options.fill, &all_words)?;
+    let start_grid = starting_grid(rows, cols, options)?;
+    // Pinned words keep their straight paths.
+    let pinned_paths = options
+        .pinned
+        .iter()
+        .map(|p| WordPath {
+            word: p.word.clone(),
+            cells: p.cells(&start_grid).collect(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut word_list = unpinned_words
+        .iter()
+        .map(|w| w.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    // Longest words first, as they are the hardest to fit.
+    word_list.sort_by_key(|w| std::cmp::Reverse(w.len()));
+
+    let mut directions = options.directions.clone();
+    directions.sort();
+    directions.dedup();
+
+    // Paths can tangle in too many ways to search them all, so instead start
+    // again from the starting grid whenever a word does not fit.
+    'attempts: for _ in 0..MAX_PLACEMENT_ATTEMPTS {
+        let mut placement_grid = start_grid.clone();
+        let mut placements = pinned_paths.clone();
+
+        for letters in &word_list {
+            let path = match place_word(&placement_grid, letters, &cells, &directions, rng) {
+                Some(path) => path,
+                None => continue 'attempts,
+            };
+
+            for (c, l) in path.iter().zip(letters) {
+                placement_grid.set_value_at_cell(c, *l);
+            }
+            placements.push(WordPath {
+                word: letters.iter().collect(),
+                cells: path,
+            });
+        }
+
+        let mut grid = placement_grid.clone();
+        grid.fill_empty_cells_with_chars(&filler, rng);
+
+        if !options.ensure_unique
+            || refill_duplicates(&mut grid, &placement_grid, &placements, &filler, rng)
+        {
+            placements.sort();
+
+            return Ok(GeneratedSnakePuzzle {
+                grid,
+                placement_grid,
+                placements,
+            });
+        }
+    }
+
+    Err(WordsearchError::Unplaceable)
+}
+
+// A path for the word from a random start, or None if no path was found
+// within the allowed number of steps.
+fn place_word<R: Rng + ?Sized>(
+    grid: &Grid,
+    letters: &[char],
+    cells: &[Cell],
+    directions: &[Direction],
+    rng: &mut R,
+) -> Option<Vec<Cell>> {
+    let mut starts = cells.to_vec();
+    starts.shuffle(rng);
+
+    let mut steps_left = MAX_PLACEMENT_STEPS;
+    for start in starts {
+        let mut path = vec![start];
+        if extend_path(grid, letters, &mut path, directions, &mut steps_left, rng) {
+            return Some(path);
+        }
+    }
+
+    None
+}
+
+// Whether the last cell of the path can hold the next letter, and the rest of
+// the word can then be placed from one of its neighbours in a random order.
+// On success, the path holds a cell for every letter.
+fn extend_path<R: Rng + ?Sized>(
+    grid: &Grid,
+    letters: &[char],
+    path: &mut Vec<Cell>,
+    directions: &[Direction],
+    steps_left: &mut usize,
+    rng: &mut R,
+) -> bool {
+    if *steps_left == 0 {
+        return false;
+    }
+    *steps_left -= 1;
+
+    let existing = grid.value_at_cell(&path[path.len() - 1]);
+    if existing != EMPTY_CHAR && existing != letters[path.len() - 1] {
+        return false;
+    }
+
+    if path.len() == letters.len() {
+        return true;
+    }
+
+    let mut next_cells = neighbours(grid, &path[path.len() - 1], directions);
+    next_cells.shuffle(rng);
+
+    for next in next_cells {
+        if path.contains(&next) {
+            continue;
+        }
+
+        path.push(next);
+        if extend_path(grid, letters, path, directions, steps_left, rng) {
+            return true;
+        }
+        path.pop();
+    }
+
+    false
+}
+
+// As `refill_duplicates` for straight words, re-fills the random characters of
+// any extra paths spelling the placed words. Returns false if that could not be
+// achieved.
+fn refill_duplicates<R: Rng + ?Sized>(
+    grid: &mut Grid,
+    placement_grid: &Grid,
+    placements: &[WordPath],
+    filler: &Filler,
+    rng: &mut R,
+) -> bool {
+    let words = placements
+        .iter()
+        .map(|p| p.word.as_str())
+        .collect::<Vec<_>>();
+
+    for _ in 0..MAX_REFILL_ATTEMPTS {
+        let duplicates = solve_grid_snake(grid, &words)
+            .unwrap() // We have already validated the words.
+            .into_iter()
+            .filter(|found| !placements.iter().any(|p| p.covers_same_cells(found)))
+            .collect::<Vec<_>>();
+
+        if duplicates.is_empty() {
+            return true;
+        }
+
+        for d in duplicates {
+            let filler_cells = d
+                .cells
+                .into_iter()
+                .filter(|c| placement_grid.value_at_cell(c) == EMPTY_CHAR)
+                .collect::<Vec<_>>();
+
+            if filler_cells.is_empty() {
+                return false;
+            }
+
+            for c in filler_cells {
+                grid.set_value_at_cell(&c, filler.random_char(rng));
+            }
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Shape, Topology, MASKED_CHAR};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn path(word: &str, cells: &[(usize, usize)]) -> WordPath {
+        WordPath {
+            word: word.to_string(),
+            cells: cells
+                .iter()
+                .map(|(row, col)| Cell {
+                    row: *row,
+                    col: *col,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_solve_grid_snake() {
+        let grid: Grid = "cax\nxto\nnox\n".parse().unwrap();
+
+        assert_eq!(
+            solve_grid_snake(&grid, &["cat", "toon", "dog"]).unwrap(),
+            vec![
+                path("cat", &[(0, 0), (0, 1), (1, 1)]),
+                path("toon", &[(1, 1), (1, 2), (2, 1), (2, 0)]),
+            ]
+        );
+
+        // No cell is used twice, and paths that read the same both ways are only counted once.
+        assert!(solve_grid_snake(&grid, &["tot"]).unwrap().is_empty());
+        assert_eq!(
+            solve_grid_snake(&grid, &["oto"]).unwrap(),
+            vec![path("oto", &[(1, 2), (1, 1), (2, 1)])]
+        );
+        assert_eq!(solve_grid_snake(&grid, &["x"]).unwrap().len(), 3);
+
+        // Paths wrap around the edges of a toroidal grid, where every cell of a
+        // 3x3 grid is next to every other.
+        assert!(solve_grid_snake(&grid, &["cox"]).unwrap().is_empty());
+        let grid = grid.with_topology(Topology::Toroidal);
+        assert_eq!(solve_grid_snake(&grid, &["cox"]).unwrap().len(), 2 * 3);
+
+        assert_eq!(
+            solve_grid_snake(&grid, &[""]),
+            Err(WordsearchError::WordTooShort {
+                word: "".to_string(),
+                min: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_solve_snake_normalized() {
+        let grid: Grid = "CAX\nXTX\n".parse().unwrap();
+        assert_eq!(
+            solve_snake_normalized(&grid, &["Cat!"], &Normalization::default()).unwrap(),
+            vec![path("Cat!", &[(0, 0), (0, 1), (1, 1)])]
+        );
+    }
+
+    #[test]
+    fn test_generate_snake_grid() {
+        let words = ["banana", "cherry", "kiwi", "lemon", "mango", "orange"];
+        let options = GenerationOptions {
+            mask: Some(Shape::Circle.mask(9, 9)),
+            ..Default::default()
+        };
+
+        for seed in 0..5 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let puzzle =
+                generate_snake_grid_with_options(9, 9, &words, &options, &mut rng).unwrap();
+
+            // The solver may take another path through the cells of a word with a repeated
+            // letter, e.g. swapping the r's of "cherry".
+            let found = solve_grid_snake(&puzzle.grid, &words).unwrap();
+            assert_eq!(found.len(), words.len());
+            for (f, p) in found.iter().zip(&puzzle.placements) {
+                assert!(f.covers_same_cells(p));
+            }
+            for p in &puzzle.placements {
+                for (c, l) in p.cells.iter().zip(p.word.chars()) {
+                    assert_eq!(puzzle.placement_grid.value_at_cell(c), l);
+                }
+            }
+            for (r, c) in iproduct!(0..9, 0..9) {
+                let cell = Cell { row: r, col: c };
+                let open = options.mask.as_ref().unwrap().is_open(&cell);
+                assert_eq!(open, puzzle.grid.value_at_cell(&cell) != MASKED_CHAR);
+            }
+        }
+
+        // Each step is in one of the allowed directions.
+        let options = GenerationOptions::with_directions(&[Direction::Right, Direction::Down]);
+        let mut rng = StdRng::seed_from_u64(0);
+        let puzzle =
+            generate_snake_grid_with_options(4, 4, &words[2..4], &options, &mut rng).unwrap();
+        for p in &puzzle.placements {
+            for pair in p.cells.windows(2) {
+                assert!(
+                    (pair[1].row, pair[1].col) == (pair[0].row, pair[0].col + 1)
+                        || (pair[1].row, pair[1].col) == (pair[0].row + 1, pair[0].col)
+                );
+            }
+        }
+
+        // Words may be as long as the grid has cells.
+        let options = GenerationOptions::default();
+        assert!(generate_snake_grid_with_options(2, 2, &["abcd"], &options, &mut rng).is_ok());
+        assert_eq!(
+            generate_snake_grid_with_options(2, 2, &["abcde"], &options, &mut rng),
+            Err(WordsearchError::WordTooLong {
+                word: "abcde".to_string(),
+                max: 4
+            })
+        );
+
+        // Pinned words keep their straight paths and the template's letters are kept.
+        let options = GenerationOptions {
+            pinned: vec!["kiwi 3,0 0,3 up-right".parse().unwrap()],
+            template: Some("____\n____\n____\n___x\n".parse().unwrap()),
+            ..Default::default()
+        };
+        let puzzle =
+            generate_snake_grid_with_options(4, 4, &["lemon"], &options, &mut rng).unwrap();
+        assert!(puzzle
+            .placements
+            .contains(&path("kiwi", &[(3, 0), (2, 1), (1, 2), (0, 3)])));
+        assert_eq!(puzzle.placements.len(), 2);
+        assert_eq!(puzzle.grid.value_at_cell(&Cell { row: 3, col: 3 }), 'x');
+    }
+}
@@ -0,0 +1,600 @@
+use crate::{Cell, Direction, Grid, WordIndex, WordLocation, WordsearchError};
+use itertools::iproduct;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::ops::Range;
+use std::slice::Iter;
+use std::str::FromStr;
+
+// Searching these directions for both the words and their reverses covers all eight directions.
+const FORWARD_DIRECTIONS: [Direction; 4] = [
+    Direction::UpRight,
+    Direction::Right,
+    Direction::DownRight,
+    Direction::Down,
+];
+
+pub trait Solver: Sync {
+    /// Returns the location of each word starting in one of the rows, sorted.
+    fn solve_rows(
+        &self,
+        grid: &Grid,
+        words: &[&str],
+        rows: Range<usize>,
+    ) -> Result<Vec<WordLocation>, WordsearchError>;
+
+    /// Returns the location of each word found in the grid, sorted.
+    fn solve(&self, grid: &Grid, words: &[&str]) -> Result<Vec<WordLocation>, WordsearchError> {
+        self.solve_rows(grid, words, 0..grid.row_count())
+    }
+
+    /// As `solve`, but shares the rows of the grid between threads.
+    /// Returns exactly the same results as `solve`.
+    #[cfg(feature = "parallel")]
+    fn solve_parallel(
+        &self,
+        grid: &Grid,
+        words: &[&str],
+    ) -> Result<Vec<WordLocation>, WordsearchError> {
+        solve_rows_in_parallel(grid, |rows| self.solve_rows(grid, words, rows))
+    }
+}
+
+/// Checks every listed word against every ray in all eight directions.
+pub struct NaiveSolver;
+
+/// As `NaiveSolver`, but skips rays that do not start with the first letter of a word.
+pub struct HashFirstLetterSolver;
+
+/// Checks the words and their reverses against rays in four directions.
+pub struct ReverseWordsSolver;
+
+/// As `ReverseWordsSolver`, but skips rays that do not start with the first letter of a word.
+pub struct ReverseHashFirstLetterSolver;
+
+/// As `ReverseWordsSolver`, but skips rays that do not start with the first two letters
+/// of a word. All words must be at least two letters long.
+pub struct ReverseHashFirstTwoLettersSolver;
+
+/// Builds a `WordIndex` for each call. Use `WordIndex` directly to solve many grids.
+pub struct TrieSolver;
+
+/// Finds every occurrence of every word.
+///
+/// Unlike the other solvers, this does not stop at the first word found along each
+/// ray, so words that are prefixes of other words (e.g. "in" and "initiate") and
+/// words that appear more than once are all reported. Palindromes and single-letter
+/// words are reported once per occurrence rather than once per direction they can
+/// be read in.
+pub struct AllOccurrencesSolver;
+
+impl Solver for NaiveSolver {
+    fn solve_rows(
+        &self,
+        grid: &Grid,
+        words: &[&str],
+        rows: Range<usize>,
+    ) -> Result<Vec<WordLocation>, WordsearchError> {
+        validate_solver_words(words, 1)?;
+
+        Ok(find_in_each_ray(
+            grid,
+            rows,
+            Direction::iterator().as_slice(),
+            |cell, direction| find_word_in_direction(vec![cell], direction, grid, words),
+        ))
+    }
+}
+
+impl Solver for HashFirstLetterSolver {
+    fn solve_rows(
+        &self,
+        grid: &Grid,
+        words: &[&str],
+        rows: Range<usize>,
+    ) -> Result<Vec<WordLocation>, WordsearchError> {
+        validate_solver_words(words, 1)?;
+
+        let mut hashed: HashSet<char> = HashSet::new();
+        for w in words.iter() {
+            hashed.insert(w.chars().next().unwrap()); // We have validated that all words are at least one char long.
+        }
+
+        Ok(find_in_each_ray(
+            grid,
+            rows,
+            Direction::iterator().as_slice(),
+            |cell, direction| {
+                if !hashed.contains(&grid.value_at_cell(&cell)) {
+                    return None;
+                }
+
+                find_word_in_direction(vec![cell], direction, grid, words)
+            },
+        ))
+    }
+}
+
+impl Solver for ReverseWordsSolver {
+    fn solve_rows(
+        &self,
+        grid: &Grid,
+        words: &[&str],
+        rows: Range<usize>,
+    ) -> Result<Vec<WordLocation>, WordsearchError> {
+        validate_solver_words(words, 1)?;
+
+        let all_words = with_reversed_words(words);
+        let all_words = all_words.iter().map(|w| w.as_str()).collect::<Vec<_>>();
+
+        Ok(find_in_each_ray(
+            grid,
+            rows,
+            &FORWARD_DIRECTIONS,
+            |cell, direction| {
+                find_word_in_direction(vec![cell], direction, grid, &all_words)
+                    .map(|found| into_listed_orientation(found, words))
+            },
+        ))
+    }
+}
+
+impl Solver for ReverseHashFirstLetterSolver {
+    fn solve_rows(
+        &self,
+        grid: &Grid,
+        words: &[&str],
+        rows: Range<usize>,
+    ) -> Result<Vec<WordLocation>, WordsearchError> {
+        validate_solver_words(words, 1)?;
+
+        let all_words = with_reversed_words(words);
+        let all_words = all_words.iter().map(|w| w.as_str()).collect::<Vec<_>>();
+
+        let mut hashed: HashSet<char> = HashSet::new();
+        for w in all_words.iter() {
+            hashed.insert(w.chars().next().unwrap()); // We have validated that all words are at least one char long.
+        }
+
+        Ok(find_in_each_ray(
+            grid,
+            rows,
+            &FORWARD_DIRECTIONS,
+            |cell, direction| {
+                if !hashed.contains(&grid.value_at_cell(&cell)) {
+                    return None;
+                }
+
+                find_word_in_direction(vec![cell], direction, grid, &all_words)
+                    .map(|found| into_listed_orientation(found, words))
+            },
+        ))
+    }
+}
+
+impl Solver for ReverseHashFirstTwoLettersSolver {
+    fn solve_rows(
+        &self,
+        grid: &Grid,
+        words: &[&str],
+        rows: Range<usize>,
+    ) -> Result<Vec<WordLocation>, WordsearchError> {
+        validate_solver_words(words, 2)?;
+
+        let all_words = with_reversed_words(words);
+        let all_words = all_words.iter().map(|w| w.as_str()).collect::<Vec<_>>();
+
+        let mut hashed: HashSet<Vec<char>> = HashSet::new();
+        for w in all_words.iter() {
+            hashed.insert(w.chars().take(2).collect()); // We have validated that all words are at least two chars long.
+        }
+
+        Ok(find_in_each_ray(
+            grid,
+            rows,
+            &FORWARD_DIRECTIONS,
+            |cell, direction| {
+                find_word_in_direction_hash(vec![cell], direction, grid, &all_words, &hashed, 2)
+                    .map(|found| into_listed_orientation(found, words))
+            },
+        ))
+    }
+}
+
+impl Solver for TrieSolver {
+    fn solve_rows(
+        &self,
+        grid: &Grid,
+        words: &[&str],
+        rows: Range<usize>,
+    ) -> Result<Vec<WordLocation>, WordsearchError> {
+        Ok(WordIndex::new(words)?.solve_rows(grid, rows))
+    }
+}
+
+impl Solver for AllOccurrencesSolver {
+    fn solve_rows(
+        &self,
+        grid: &Grid,
+        words: &[&str],
+        rows: Range<usize>,
+    ) -> Result<Vec<WordLocation>, WordsearchError> {
+        validate_solver_words(words, 1)?;
+
+        let all_words = with_reversed_words(words);
+        let all_words = all_words.iter().map(|w| w.as_str()).collect::<Vec<_>>();
+        let index = WordIndex::new(&all_words)?;
+
+        Ok(find_in_each_ray(
+            grid,
+            rows,
+            &FORWARD_DIRECTIONS,
+            |cell, direction| {
+                let mut word_locations = Vec::new();
+
+                for found in index.find_all_words_in_direction(cell, direction, grid) {
+                    // Single cells read the same in every direction, so only count them once.
+                    if found.start_cell == found.end_cell && *direction != Direction::Right {
+                        continue;
+                    }
+
+                    let reversed = found.word.chars().rev().collect::<String>();
+
+                    if reversed != found.word && words.contains(&reversed.as_str()) {
+                        word_locations.push(found.clone().into_reversed_location());
+                    }
+
+                    if words.contains(&found.word.as_str()) {
+                        word_locations.push(found);
+                    }
+                }
+
+                word_locations
+            },
+        ))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SolverStrategy {
+    Naive,
+    HashFirstLetter,
+    ReverseWords,
+    ReverseHashFirstLetter,
+    ReverseHashFirstTwoLetters,
+    Trie,
+    AllOccurrences,
+}
+
+impl SolverStrategy {
+    pub fn iterator() -> Iter<'static, SolverStrategy> {
+        static STRATEGIES: [SolverStrategy; 7] = [
+            SolverStrategy::Naive,
+            SolverStrategy::HashFirstLetter,
+            SolverStrategy::ReverseWords,
+            SolverStrategy::ReverseHashFirstLetter,
+            SolverStrategy::ReverseHashFirstTwoLetters,
+            SolverStrategy::Trie,
+            SolverStrategy::AllOccurrences,
+        ];
+        STRATEGIES.iter()
+    }
+
+    pub fn solver(&self) -> Box<dyn Solver> {
+        match self {
+            SolverStrategy::Naive => Box::new(NaiveSolver),
+            SolverStrategy::HashFirstLetter => Box::new(HashFirstLetterSolver),
+            SolverStrategy::ReverseWords => Box::new(ReverseWordsSolver),
+            SolverStrategy::ReverseHashFirstLetter => Box::new(ReverseHashFirstLetterSolver),
+            SolverStrategy::ReverseHashFirstTwoLetters => {
+                Box::new(ReverseHashFirstTwoLettersSolver)
+            }
+            SolverStrategy::Trie => Box::new(TrieSolver),
+            SolverStrategy::AllOccurrences => Box::new(AllOccurrencesSolver),
+        }
+    }
+}
+
+impl fmt::Display for SolverStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SolverStrategy::Naive => "naive",
+            SolverStrategy::HashFirstLetter => "hash-first-letter",
+            SolverStrategy::ReverseWords => "reverse-words",
+            SolverStrategy::ReverseHashFirstLetter => "reverse-hash-first-letter",
+            SolverStrategy::ReverseHashFirstTwoLetters => "reverse-hash-first-two-letters",
+            SolverStrategy::Trie => "trie",
+            SolverStrategy::AllOccurrences => "all-occurrences",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for SolverStrategy {
+    type Err = WordsearchError;
+
+    /// Parses the names produced by `Display`, e.g. `hash-first-letter`, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        SolverStrategy::iterator()
+            .find(|st| st.to_string() == s)
+            .copied()
+            .ok_or(WordsearchError::UnknownSolverStrategy(s))
+    }
+}
+
+pub fn solve(
+    grid: &Grid,
+    words: &[&str],
+    strategy: SolverStrategy,
+) -> Result<Vec<WordLocation>, WordsearchError> {
+    strategy.solver().solve(grid, words)
+}
+
+#[cfg(feature = "parallel")]
+pub fn solve_parallel(
+    grid: &Grid,
+    words: &[&str],
+    strategy: SolverStrategy,
+) -> Result<Vec<WordLocation>, WordsearchError> {
+    strategy.solver().solve_parallel(grid, words)
+}
+
+pub fn solve_grid_reverse_hash_first_two_letters(
+    grid: &Grid,
+    words: &[&str],
+) -> Result<Vec<WordLocation>, WordsearchError> {
+    ReverseHashFirstTwoLettersSolver.solve(grid, words)
+}
+
+pub fn solve_grid_reverse_hash_first_letter(
+    grid: &Grid,
+    words: &[&str],
+) -> Result<Vec<WordLocation>, WordsearchError> {
+    ReverseHashFirstLetterSolver.solve(grid, words)
+}
+
+pub fn solve_grid_hash_first_letter(
+    grid: &Grid,
+    words: &[&str],
+) -> Result<Vec<WordLocation>, WordsearchError> {
+    HashFirstLetterSolver.solve(grid, words)
+}
+
+pub fn solve_grid_naive(grid: &Grid, words: &[&str]) -> Result<Vec<WordLocation>, WordsearchError> {
+    NaiveSolver.solve(grid, words)
+}
+
+pub fn solve_grid_reverse_words(
+    grid: &Grid,
+    words: &[&str],
+) -> Result<Vec<WordLocation>, WordsearchError> {
+    ReverseWordsSolver.solve(grid, words)
+}
+
+pub fn solve_grid_all_occurrences(
+    grid: &Grid,
+    words: &[&str],
+) -> Result<Vec<WordLocation>, WordsearchError> {
+    AllOccurrencesSolver.solve(grid, words)
+}
+
+/// Counts how many times each word occurs in the provided locations,
+/// including words that do not occur at all.
+pub fn count_occurrences(words: &[&str], locations: &[WordLocation]) -> BTreeMap<String, usize> {
+    let mut counts = words
+        .iter()
+        .map(|w| (w.to_string(), 0))
+        .collect::<BTreeMap<_, _>>();
+
+    for l in locations {
+        if let Some(count) = counts.get_mut(&l.word) {
+            *count += 1;
+        }
+    }
+
+    counts
+}
+
+pub(crate) fn validate_solver_words(words: &[&str], min: usize) -> Result<(), WordsearchError> {
+    match words.iter().find(|w| w.chars().count() < min) {
+        Some(w) => Err(WordsearchError::WordTooShort {
+            word: w.to_string(),
+            min,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Runs `find` on the ray starting at each cell of the rows in each of the directions,
+/// returning everything found, sorted.
+pub(crate) fn find_in_each_ray<F, I>(
+    grid: &Grid,
+    rows: Range<usize>,
+    directions: &[Direction],
+    mut find: F,
+) -> Vec<WordLocation>
+where
+    F: FnMut(Cell, &Direction) -> I,
+    I: IntoIterator<Item = WordLocation>,
+{
+    let mut word_locations = iproduct!(rows, 0..grid.col_count(), directions.iter())
+        .flat_map(|(row, col, direction)| find(Cell { row, col }, direction))
+        .collect::<Vec<_>>();
+
+    word_locations.sort();
+    word_locations
+}
+
+/// Splits the rows of the grid into a few bands per thread and runs `solve_rows`
+/// on each band in parallel, merging the results in sorted order.
+#[cfg(feature = "parallel")]
+pub(crate) fn solve_rows_in_parallel<F>(
+    grid: &Grid,
+    solve_rows: F,
+) -> Result<Vec<WordLocation>, WordsearchError>
+where
+    F: Fn(Range<usize>) -> Result<Vec<WordLocation>, WordsearchError> + Sync,
+{
+    let row_count = grid.row_count();
+    let band_size = std::cmp::max(1, row_count / (rayon::current_num_threads() * 4));
+
+    let bands = (0..row_count)
+        .step_by(band_size)
+        .map(|start| start..std::cmp::min(start + band_size, row_count))
+        .collect::<Vec<_>>();
+
+    let mut word_locations = bands
+        .into_par_iter()
+        .map(&solve_rows)
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+    word_locations.sort();
+    Ok(word_locations)
+}
+
+// The words followed by each of the words reversed.
+fn with_reversed_words(words: &[&str]) -> Vec<String> {
+    words
+        .iter()
+        .map(|w| w.to_string())
+        .chain(words.iter().map(|w| w.chars().rev().collect::<String>()))
+        .collect()
+}
+
+fn into_listed_orientation(found: WordLocation, words: &[&str]) -> WordLocation {
+    // If the originally-provided list of words does not contain the found word,
+    // the found word must be reversed.
+    if words.contains(&found.word.as_str()) {
+        found
+    } else {
+        found.into_reversed_location()
+    }
+}
+
+fn find_word_in_direction_hash(
+    mut cells: Vec<Cell>,
+    direction: &Direction,
+    grid: &Grid,
+    words: &[&str],
+    hashed: &HashSet<Vec<char>>,
+    hashed_length: usize,
+) -> Option<WordLocation> {
+    let current_cell = cells[cells.len() - 1];
+
+    if cells.len() == hashed_length
+        && !hashed.contains(
+            &cells
+                .iter()
+                .map(|c| grid.value_at_cell(c))
+                .collect::<Vec<char>>(),
+        )
+    {
+        return None;
+    }
+
+    let maybe_word = cells
+        .iter()
+        .map(|c| grid.value_at_cell(c))
+        .collect::<String>();
+
+    if words.contains(&maybe_word.as_str()) {
+        return Some(WordLocation {
+            word: maybe_word,
+            start_cell: cells[0],
+            end_cell: cells[cells.len() - 1],
+            direction: direction.to_owned(),
+        });
+    }
+
+    // Stop before a word in a toroidal grid wraps around onto its own first cell.
+    if cells.len() > grid.cells_remaining_in_direction(&cells[0], direction) {
+        return None;
+    }
+
+    let next_cell = grid.neighbor(&current_cell, direction)?;
+    cells.push(next_cell);
+    find_word_in_direction_hash(cells, direction, grid, words, hashed, hashed_length)
+}
+
+fn find_word_in_direction(
+    mut cells: Vec<Cell>,
+    direction: &Direction,
+    grid: &Grid,
+    words: &[&str],
+) -> Option<WordLocation> {
+    let current_cell = cells[cells.len() - 1];
+
+    let maybe_word = cells
+        .iter()
+        .map(|c| grid.value_at_cell(c))
+        .collect::<String>();
+
+    if words.contains(&maybe_word.as_str()) {
+        return Some(WordLocation {
+            word: maybe_word,
+            start_cell: cells[0],
+            end_cell: cells[cells.len() - 1],
+            direction: direction.to_owned(),
+        });
+    }
+
+    // Stop before a word in a toroidal grid wraps around onto its own first cell.
+    if cells.len() > grid.cells_remaining_in_direction(&cells[0], direction) {
+        return None;
+    }
+
+    let next_cell = grid.neighbor(&current_cell, direction)?;
+    cells.push(next_cell);
+    find_word_in_direction(cells, direction, grid, words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "parallel")]
+    use crate::generate_grid_from_seed;
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_solve_parallel() {
+        let words = [
+            "anxious", "border", "guess", "hope", "mind", "nose", "robin",
+        ];
+        let grid = generate_grid_from_seed(40, 30, &words, 0).unwrap().grid;
+
+        for strategy in SolverStrategy::iterator() {
+            assert_eq!(
+                solve_parallel(&grid, &words, *strategy).unwrap(),
+                solve(&grid, &words, *strategy).unwrap()
+            );
+        }
+
+        assert_eq!(
+            solve_parallel(&grid, &["a", ""], SolverStrategy::Naive),
+            Err(WordsearchError::WordTooShort {
+                word: "".to_string(),
+                min: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_solver_strategy_from_str() {
+        for strategy in SolverStrategy::iterator() {
+            assert_eq!(strategy.to_string().parse(), Ok(*strategy));
+        }
+        assert_eq!("Trie".parse(), Ok(SolverStrategy::Trie));
+        assert_eq!(
+            "fastest".parse::<SolverStrategy>(),
+            Err(WordsearchError::UnknownSolverStrategy(
+                "fastest".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,52 @@
+/// Parses a word list with one word per line.
+///
+/// Everything after a `#` is a comment, and blank lines are ignored. The words
+/// are normalized with `normalize_words`.
+pub fn parse_word_list(text: &str) -> Vec<String> {
+    normalize_words(
+        &text
+            .lines()
+            .map(|line| match line.find('#') {
+                Some(i) => &line[..i],
+                None => line,
+            })
+            .collect::<Vec<&str>>(),
+    )
+}
+
+/// Trims and lowercases each word, dropping empty words and any word already seen.
+///
+/// The remaining words keep the order they were first listed in.
+pub fn normalize_words<S: AsRef<str>>(words: &[S]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+
+    for w in words {
+        let w = w.as_ref().trim().to_lowercase();
+        if !w.is_empty() && !normalized.contains(&w) {
+            normalized.push(w);
+        }
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_word_list() {
+        let text = "# Animals\nrobin\n\n  Badger  \nrobin # again\n\t\n#otter\nstoat#weasel\n";
+
+        assert_eq!(parse_word_list(text), vec!["robin", "badger", "stoat"]);
+        assert!(parse_word_list("").is_empty());
+    }
+
+    #[test]
+    fn test_normalize_words() {
+        assert_eq!(
+            normalize_words(&[" Hope", "hope ", "", "MIND", "  "]),
+            vec!["hope", "mind"]
+        );
+    }
+}
@@ -4,36 +4,36 @@ use wordsearch::*;
 fn test_generate_grid() {
     let words2 = ["if", "it", "to"];
     let valid_grids = vec![
-        Grid::new(&[vec!['i', 'f'], vec!['t', 'o']]),
-        Grid::new(&[vec!['i', 'f'], vec!['o', 't']]),
-        Grid::new(&[vec!['i', 't'], vec!['o', 'f']]),
-        Grid::new(&[vec!['i', 't'], vec!['f', 'o']]),
-        Grid::new(&[vec!['i', 'o'], vec!['t', 'f']]),
-        Grid::new(&[vec!['i', 'o'], vec!['f', 't']]),
-        Grid::new(&[vec!['f', 'i'], vec!['t', 'o']]),
-        Grid::new(&[vec!['f', 'i'], vec!['o', 't']]),
-        Grid::new(&[vec!['f', 'o'], vec!['t', 'i']]),
-        Grid::new(&[vec!['f', 'o'], vec!['i', 't']]),
-        Grid::new(&[vec!['f', 't'], vec!['o', 'i']]),
-        Grid::new(&[vec!['f', 't'], vec!['i', 'o']]),
-        Grid::new(&[vec!['t', 'i'], vec!['o', 'f']]),
-        Grid::new(&[vec!['t', 'i'], vec!['f', 'o']]),
-        Grid::new(&[vec!['t', 'o'], vec!['i', 'f']]),
-        Grid::new(&[vec!['t', 'o'], vec!['f', 'i']]),
-        Grid::new(&[vec!['t', 'f'], vec!['o', 'i']]),
-        Grid::new(&[vec!['t', 'f'], vec!['i', 'o']]),
-        Grid::new(&[vec!['o', 'i'], vec!['t', 'f']]),
-        Grid::new(&[vec!['o', 'i'], vec!['f', 't']]),
-        Grid::new(&[vec!['o', 't'], vec!['i', 'f']]),
-        Grid::new(&[vec!['o', 't'], vec!['f', 'i']]),
-        Grid::new(&[vec!['o', 'f'], vec!['t', 'i']]),
-        Grid::new(&[vec!['o', 'f'], vec!['i', 't']]),
+        Grid::new(&[vec!['i', 'f'], vec!['t', 'o']]).unwrap(),
+        Grid::new(&[vec!['i', 'f'], vec!['o', 't']]).unwrap(),
+        Grid::new(&[vec!['i', 't'], vec!['o', 'f']]).unwrap(),
+        Grid::new(&[vec!['i', 't'], vec!['f', 'o']]).unwrap(),
+        Grid::new(&[vec!['i', 'o'], vec!['t', 'f']]).unwrap(),
+        Grid::new(&[vec!['i', 'o'], vec!['f', 't']]).unwrap(),
+        Grid::new(&[vec!['f', 'i'], vec!['t', 'o']]).unwrap(),
+        Grid::new(&[vec!['f', 'i'], vec!['o', 't']]).unwrap(),
+        Grid::new(&[vec!['f', 'o'], vec!['t', 'i']]).unwrap(),
+        Grid::new(&[vec!['f', 'o'], vec!['i', 't']]).unwrap(),
+        Grid::new(&[vec!['f', 't'], vec!['o', 'i']]).unwrap(),
+        Grid::new(&[vec!['f', 't'], vec!['i', 'o']]).unwrap(),
+        Grid::new(&[vec!['t', 'i'], vec!['o', 'f']]).unwrap(),
+        Grid::new(&[vec!['t', 'i'], vec!['f', 'o']]).unwrap(),
+        Grid::new(&[vec!['t', 'o'], vec!['i', 'f']]).unwrap(),
+        Grid::new(&[vec!['t', 'o'], vec!['f', 'i']]).unwrap(),
+        Grid::new(&[vec!['t', 'f'], vec!['o', 'i']]).unwrap(),
+        Grid::new(&[vec!['t', 'f'], vec!['i', 'o']]).unwrap(),
+        Grid::new(&[vec!['o', 'i'], vec!['t', 'f']]).unwrap(),
+        Grid::new(&[vec!['o', 'i'], vec!['f', 't']]).unwrap(),
+        Grid::new(&[vec!['o', 't'], vec!['i', 'f']]).unwrap(),
+        Grid::new(&[vec!['o', 't'], vec!['f', 'i']]).unwrap(),
+        Grid::new(&[vec!['o', 'f'], vec!['t', 'i']]).unwrap(),
+        Grid::new(&[vec!['o', 'f'], vec!['i', 't']]).unwrap(),
     ];
 
-    let grid2 = generate_grid(2, 2, &words2).unwrap();
+    let grid2 = generate_grid(2, 2, &words2).unwrap().grid;
     assert!(valid_grids.contains(&grid2));
 
-    let found_words2 = solve_grid_naive(&grid2, &words2);
+    let found_words2 = solve_grid_naive(&grid2, &words2).unwrap();
     assert_eq!(found_words2.len(), words2.len());
 
     for (w, fw) in words2.iter().zip(found_words2.iter()) {
@@ -41,12 +41,12 @@ fn test_generate_grid() {
     }
 
     let words3 = ["now", "pen", "tan", "top"];
-    let grid3 = generate_grid(3, 3, &words3).unwrap();
+    let grid3 = generate_grid(3, 3, &words3).unwrap().grid;
 
     assert_eq!(grid3.col_count(), 3);
     assert_eq!(grid3.row_count(), 3);
 
-    let found_words3 = solve_grid_naive(&grid3, &words3);
+    let found_words3 = solve_grid_naive(&grid3, &words3).unwrap();
 
     assert_eq!(found_words3.len(), words3.len());
 
@@ -55,12 +55,12 @@ fn test_generate_grid() {
     }
 
     let words3x4 = ["pin", "post", "sit", "tent", "tie"];
-    let grid3x4 = generate_grid(3, 4, &words3x4).unwrap();
+    let grid3x4 = generate_grid(3, 4, &words3x4).unwrap().grid;
 
     assert_eq!(grid3x4.col_count(), 4);
     assert_eq!(grid3x4.row_count(), 3);
 
-    let found_words3x4 = solve_grid_naive(&grid3x4, &words3x4);
+    let found_words3x4 = solve_grid_naive(&grid3x4, &words3x4).unwrap();
 
     assert_eq!(found_words3x4.len(), words3x4.len());
 
@@ -71,20 +71,22 @@ fn test_generate_grid() {
 
 #[test]
 fn test_solve_grid() {
-    let grid = Grid::new(&vec![
+    let grid = Grid::new(&[
         vec!['t', 'o', 'p'],
         vec!['a', 'e', 'z'],
         vec!['n', 'o', 'w'],
-    ]);
+    ])
+    .unwrap();
 
     let words = ["now", "pen", "tan", "top"];
 
-    let found_words_naive = solve_grid_naive(&grid, &words);
-    let found_words_hash_first_letter = solve_grid_hash_first_letter(&grid, &words);
-    let found_words_reverse = solve_grid_reverse_words(&grid, &words);
-    let found_words_reverse_hash_first_letter = solve_grid_reverse_hash_first_letter(&grid, &words);
+    let found_words_naive = solve_grid_naive(&grid, &words).unwrap();
+    let found_words_hash_first_letter = solve_grid_hash_first_letter(&grid, &words).unwrap();
+    let found_words_reverse = solve_grid_reverse_words(&grid, &words).unwrap();
+    let found_words_reverse_hash_first_letter =
+        solve_grid_reverse_hash_first_letter(&grid, &words).unwrap();
     let found_words_reverse_hash_first_two_letter =
-        solve_grid_reverse_hash_first_two_letters(&grid, &words);
+        solve_grid_reverse_hash_first_two_letters(&grid, &words).unwrap();
 
     assert_eq!(found_words_naive, found_words_hash_first_letter);
     assert_eq!(found_words_naive, found_words_reverse);
@@ -134,3 +136,58 @@ fn test_solve_grid() {
         }
     );
 }
+
+#[test]
+fn test_unicode_puzzles() {
+    let word_lists = [
+        vec!["café", "naïve", "façade", "über", "señor"],
+        vec!["θάλασσα", "ήλιος", "νερό", "φως", "γάτα"],
+        vec!["привет", "мир", "книга", "дом", "солнце"],
+        vec!["사랑", "한국어", "바다", "하늘", "고양이"],
+    ];
+
+    for words in &word_lists {
+        for seed in 0..5 {
+            let puzzle = generate_grid_from_seed(7, 7, words, seed).unwrap();
+
+            let mut sorted_words = words.clone();
+            sorted_words.sort();
+            assert_eq!(
+                puzzle
+                    .placements
+                    .iter()
+                    .map(|p| p.word.as_str())
+                    .collect::<Vec<_>>(),
+                sorted_words
+            );
+
+            for strategy in SolverStrategy::iterator() {
+                assert_eq!(
+                    solve(&puzzle.grid, words, *strategy).unwrap(),
+                    puzzle.placements
+                );
+            }
+
+            for p in &puzzle.placements {
+                let cells = puzzle.placement_grid.one_word_grid(p).unwrap().to_string();
+                assert_eq!(
+                    cells.chars().filter(|c| !"_ \n".contains(*c)).count(),
+                    p.word.chars().count()
+                );
+            }
+
+            assert_eq!(puzzle.grid.to_string().parse(), Ok(puzzle.grid));
+        }
+    }
+
+    // Words are measured in chars, not bytes.
+    let puzzle = generate_grid_from_seed(1, 4, &["ñoño"], 0).unwrap();
+    assert_eq!(puzzle.placements[0].word, "ñoño");
+    assert_eq!(
+        generate_grid(1, 3, &["ñoño"]),
+        Err(WordsearchError::WordTooLong {
+            word: "ñoño".to_string(),
+            max: 3
+        })
+    );
+}